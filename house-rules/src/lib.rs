@@ -0,0 +1,76 @@
+#![warn(clippy::pedantic)]
+#![deny(rust_2018_idioms)]
+
+//! House rules shared by the HTTP server, which checks them when a game is
+//! created, and the game server, which plays by them.
+
+use serde::{Deserialize, Serialize};
+
+pub const STARTING_CASH: u32 = 1500;
+
+/// Variants a group can pick when the game is created, handed over by the
+/// HTTP server as JSON in `MONOPOLY_HOUSE_RULES`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct HouseRules {
+    /// Taxes and fines paid to the bank pile up for whoever lands on Free Parking.
+    pub free_parking_jackpot: bool,
+    /// Landing exactly on Go pays twice the salary.
+    pub double_go_salary: bool,
+    /// Declined deeds are put up for auction instead of staying with the bank.
+    pub auctions: bool,
+    pub starting_cash: u32,
+    /// Houses must be spread evenly across a color group.
+    pub even_build: bool,
+    /// Turns played before the wealthiest player wins, if limited.
+    pub max_turns: Option<u32>,
+}
+
+impl Default for HouseRules {
+    fn default() -> Self {
+        HouseRules {
+            free_parking_jackpot: false,
+            double_go_salary: false,
+            auctions: true,
+            starting_cash: STARTING_CASH,
+            even_build: true,
+            max_turns: None,
+        }
+    }
+}
+
+impl HouseRules {
+    /// Reads the rules from `MONOPOLY_HOUSE_RULES`, or the defaults if unset.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the variable is not a valid set of house rules.
+    pub fn from_env() -> serde_json::Result<HouseRules> {
+        match std::env::var("MONOPOLY_HOUSE_RULES") {
+            Ok(rules) => serde_json::from_str(&rules),
+            Err(_) => Ok(HouseRules::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_rules_take_their_defaults() {
+        let rules = serde_json::from_str::<HouseRules>(r#"{"starting_cash": 2000}"#).unwrap();
+
+        assert_eq!(rules.starting_cash, 2000);
+        assert!(rules.auctions);
+        assert!(rules.even_build);
+        assert_eq!(rules.max_turns, None);
+    }
+
+    #[test]
+    fn unknown_rules_are_rejected() {
+        assert!(serde_json::from_str::<HouseRules>(r#"{"startingcash": 2000}"#).is_err());
+        assert!(serde_json::from_str::<HouseRules>(r#"{"auctions": "yes"}"#).is_err());
+    }
+}
@@ -0,0 +1,37 @@
+use async_std::os::unix::net::UnixStream;
+use eyre::{bail, Result};
+use serde_json::Value;
+use soketto::handshake::{Client, ServerResponse};
+
+use crate::games::{self, GAMES};
+
+/// Sends a control request to game `code` over its socket and returns the
+/// game's reply, or `None` if there is no such game.
+///
+/// Requests are JSON objects naming their kind under `control`: `shutdown`,
+/// `broadcast` with a `message`, or `configure` with `rules` and/or an
+/// `auction_timeout` in seconds.
+pub async fn send(code: &str, request: &Value) -> Result<Option<Value>> {
+    let Some(key) = GAMES.lock().control_key(code) else {
+        return Ok(None);
+    };
+
+    let stream = UnixStream::connect(games::socket_path(code)).await?;
+
+    let resource = format!("/internal/{key}");
+    let mut client = Client::new(stream, "127.0.0.1", &resource);
+
+    let ServerResponse::Accepted { .. } = client.handshake().await? else {
+        bail!("Game {} refused the control channel", code);
+    };
+
+    let (mut sender, mut receiver) = client.into_builder().finish();
+
+    sender.send_text(request.to_string()).await?;
+
+    let mut data = vec![];
+    receiver.receive_data(&mut data).await?;
+    sender.close().await.ok();
+
+    Ok(Some(serde_json::from_slice(&data)?))
+}
@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::{Child, Command};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use log::{error, info, warn};
+use parking_lot::Mutex;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Where game servers and the internal API bind their sockets.
+pub const SOCKS_DIR: &str = "/monopoly_socks";
+
+/// How often exited game processes are looked for.
+const REAP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Games that crash sooner than this after starting are not relaunched, as
+/// their saved state would most likely crash them again.
+const MIN_RELAUNCH_UPTIME: Duration = Duration::from_secs(10);
+
+/// How long a game left from the last run gets to exit once told to stop.
+const ORPHAN_EXIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub static GAMES: LazyLock<Mutex<Registry>> = LazyLock::new(|| Mutex::new(Registry::default()));
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Lobby,
+    InProgress,
+    Finished,
+}
+
+#[derive(Debug, Clone)]
+pub struct Status {
+    pub players: usize,
+    pub phase: Phase,
+}
+
+/// What a game tells us about itself. Mirrors the game server's copy.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "notice", rename_all = "snake_case")]
+pub enum Notice {
+    Status { players: usize, phase: Phase },
+    Joined { username: String },
+    Ended { winner: String },
+}
+
+/// A game server process spawned by `create_game`.
+#[derive(Debug)]
+pub struct Game {
+    pub code: String,
+    pub pid: u32,
+    pub started: SystemTime,
+    pub status: Status,
+    pub winner: Option<String>,
+    /// Proves to the game that control requests come from us.
+    control_key: String,
+    child: Child,
+}
+
+impl Game {
+    /// The game as listed by the public API.
+    pub fn listing(&self) -> Value {
+        json!({
+            "code": self.code,
+            "players": self.status.players,
+            "phase": self.status.phase,
+            "winner": self.winner,
+            "created": self.started.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+        })
+    }
+}
+
+/// Live game processes by game code.
+#[derive(Debug, Default)]
+pub struct Registry {
+    games: HashMap<String, Game>,
+}
+
+pub fn socket_path(code: &str) -> PathBuf {
+    PathBuf::from(SOCKS_DIR).join(code)
+}
+
+/// Where game `code` keeps its snapshot, if games save their state under
+/// `MONOPOLY_DATA_DIR`.
+fn snapshot_path(code: &str) -> Option<PathBuf> {
+    let dir = std::env::var("MONOPOLY_DATA_DIR").ok()?;
+    Some(PathBuf::from(dir).join(format!("{code}.snapshot.json")))
+}
+
+fn has_saved_state(code: &str) -> bool {
+    snapshot_path(code).is_some_and(|path| path.exists())
+}
+
+/// A fresh host or control key.
+pub fn key() -> String {
+    (0..128)
+        .map(|_| rand::thread_rng().gen_range(b'A'..=b'Z') as char)
+        .collect()
+}
+
+/// Starts the game server for `code`. A game with saved state picks up where
+/// it left off, keeping the host key it was first given.
+pub fn spawn(
+    code: &str,
+    host_key: &str,
+    control_key: &str,
+    rules: Option<&str>,
+) -> std::io::Result<Child> {
+    let mut command = Command::new(std::env::var("MONOPOLY_GAME_BIN_PATH").unwrap());
+    command
+        .env(
+            "MONOPOLY_CHOWN_ID",
+            std::env::var("MONOPOLY_CHOWN_ID").unwrap(),
+        )
+        .env("MONOPOLY_GAME_PATH", socket_path(code))
+        .env("MONOPOLY_HOST_KEY", host_key)
+        .env("MONOPOLY_CONTROL_KEY", control_key);
+
+    if let Some(rules) = rules {
+        command.env("MONOPOLY_HOUSE_RULES", rules);
+    }
+
+    command.spawn()
+}
+
+/// Relaunches game `code` from its saved state.
+fn relaunch(code: &str) -> std::io::Result<(String, Child)> {
+    let control_key = key();
+    let child = spawn(code, &key(), &control_key, None)?;
+
+    Ok((control_key, child))
+}
+
+impl Registry {
+    pub fn insert(&mut self, code: &str, control_key: &str, child: Child) {
+        info!("Game {} started as PID {}", code, child.id());
+
+        self.games.insert(
+            code.to_string(),
+            Game {
+                code: code.to_string(),
+                pid: child.id(),
+                started: SystemTime::now(),
+                status: Status {
+                    players: 0,
+                    phase: Phase::Lobby,
+                },
+                winner: None,
+                control_key: control_key.to_string(),
+                child,
+            },
+        );
+    }
+
+    pub fn contains(&self, code: &str) -> bool {
+        self.games.contains_key(code)
+    }
+
+    pub fn get(&self, code: &str) -> Option<&Game> {
+        self.games.get(code)
+    }
+
+    /// Live games, oldest first.
+    pub fn list(&self) -> Vec<&Game> {
+        let mut games = self.games.values().collect::<Vec<_>>();
+        games.sort_by_key(|game| game.started);
+        games
+    }
+
+    pub fn control_key(&self, code: &str) -> Option<String> {
+        self.games.get(code).map(|game| game.control_key.clone())
+    }
+
+    /// Records what game `code` reported, returning whether it is known.
+    pub fn notify(&mut self, code: &str, notice: Notice) -> bool {
+        let Some(game) = self.games.get_mut(code) else {
+            return false;
+        };
+
+        match notice {
+            Notice::Status { players, phase } => game.status = Status { players, phase },
+            Notice::Joined { username } => info!("{} joined game {}", username, code),
+            Notice::Ended { winner } => {
+                info!("Game {} won by {}", code, winner);
+                game.winner = Some(winner);
+            }
+        }
+
+        true
+    }
+
+    /// Waits on every game that has exited, logging how it went and removing
+    /// its socket. Games that crashed with saved state are relaunched.
+    fn reap(&mut self) {
+        let mut crashed = vec![];
+
+        self.games.retain(|_, game| {
+            let status = match game.child.try_wait() {
+                Ok(Some(status)) => status,
+                Ok(None) => return true,
+                Err(err) => {
+                    error!(
+                        "Failed to wait on game {} (PID {}): {}",
+                        game.code, game.pid, err
+                    );
+                    return true;
+                }
+            };
+
+            let uptime = game.started.elapsed().unwrap_or_default().as_secs();
+            if status.success() {
+                info!(
+                    "Game {} (PID {}) exited after {}s",
+                    game.code, game.pid, uptime
+                );
+            } else {
+                warn!(
+                    "Game {} (PID {}) exited after {}s with {}",
+                    game.code, game.pid, uptime, status
+                );
+
+                if Duration::from_secs(uptime) >= MIN_RELAUNCH_UPTIME {
+                    crashed.push(game.code.clone());
+                }
+            }
+
+            remove_socket(&game.code);
+            false
+        });
+
+        for code in crashed.into_iter().filter(|code| has_saved_state(code)) {
+            match relaunch(&code) {
+                Ok((control_key, child)) => self.insert(&code, &control_key, child),
+                Err(err) => error!("Failed to relaunch game {}: {}", code, err),
+            }
+        }
+    }
+}
+
+fn remove_socket(code: &str) {
+    match std::fs::remove_file(socket_path(code)) {
+        Ok(()) => info!("Removed socket of game {}", code),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => (),
+        Err(err) => error!("Failed to remove socket of game {}: {}", code, err),
+    }
+}
+
+/// Whether `name` looks like a game code, as opposed to the internal socket.
+fn is_game_code(name: &str) -> bool {
+    name.len() == 8 && name.bytes().all(|byte| byte.is_ascii_uppercase())
+}
+
+/// Finds the game server bound to `code`'s socket by its environment.
+fn find_pid(code: &str) -> Option<u32> {
+    let var = format!("MONOPOLY_GAME_PATH={}", socket_path(code).display());
+
+    std::fs::read_dir("/proc")
+        .ok()?
+        .flatten()
+        .find_map(|entry| {
+            let pid = entry.file_name().to_str()?.parse().ok()?;
+            let environ = std::fs::read(entry.path().join("environ")).ok()?;
+
+            environ
+                .split(|byte| *byte == 0)
+                .any(|entry| entry == var.as_bytes())
+                .then_some(pid)
+        })
+}
+
+/// Stops game `code`, returning whether it exited in time.
+fn terminate(code: &str) -> bool {
+    let Some(pid) = find_pid(code) else {
+        return false;
+    };
+
+    let killed = Command::new("kill")
+        .arg(pid.to_string())
+        .status()
+        .is_ok_and(|status| status.success());
+    if !killed {
+        return false;
+    }
+
+    let proc = PathBuf::from(format!("/proc/{pid}"));
+    let deadline = Instant::now() + ORPHAN_EXIT_TIMEOUT;
+
+    while proc.exists() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    true
+}
+
+/// Removes game sockets left behind by a previous run. Games that outlived
+/// it are stopped first: nothing in this run holds their control key or can
+/// reap them. Those with saved state come back under this run through
+/// `relaunch_saved`.
+pub fn remove_stale_sockets() -> std::io::Result<()> {
+    for entry in std::fs::read_dir(SOCKS_DIR)? {
+        let name = entry?.file_name();
+
+        let Some(code) = name.to_str().filter(|name| is_game_code(name)) else {
+            continue;
+        };
+
+        if std::os::unix::net::UnixStream::connect(socket_path(code)).is_ok() {
+            if !terminate(code) {
+                warn!("Failed to stop game {} left from the last run", code);
+                continue;
+            }
+
+            info!("Stopped game {} left from the last run", code);
+        }
+
+        remove_socket(code);
+    }
+
+    Ok(())
+}
+
+/// Relaunches games that saved their state but are no longer running, such
+/// as those that went down with the machine. Run after stale sockets are
+/// removed, so a game with a socket is one still running.
+pub fn relaunch_saved() -> std::io::Result<()> {
+    let Ok(dir) = std::env::var("MONOPOLY_DATA_DIR") else {
+        return Ok(());
+    };
+
+    let mut games = GAMES.lock();
+
+    for entry in std::fs::read_dir(dir)? {
+        let name = entry?.file_name();
+
+        let Some(code) = name
+            .to_str()
+            .and_then(|name| name.strip_suffix(".snapshot.json"))
+            .filter(|code| is_game_code(code))
+        else {
+            continue;
+        };
+
+        if games.contains(code) || socket_path(code).exists() {
+            continue;
+        }
+
+        match relaunch(code) {
+            Ok((control_key, child)) => games.insert(code, &control_key, child),
+            Err(err) => error!("Failed to relaunch game {}: {}", code, err),
+        }
+    }
+
+    Ok(())
+}
+
+/// Reaps game processes as they exit, forever.
+pub async fn reap() {
+    loop {
+        async_std::task::sleep(REAP_INTERVAL).await;
+        GAMES.lock().reap();
+    }
+}
@@ -1,45 +1,104 @@
 #![warn(clippy::pedantic)]
 #![deny(rust_2018_idioms)]
 
-use std::path::Path;
-use std::process::Command;
-
 use async_std::os::unix::net::UnixListener;
 use async_std::prelude::FutureExt;
 use eyre::Result;
+use house_rules::HouseRules;
 use log::LevelFilter;
 use rand::Rng;
 use tide::prelude::*;
-use tide::Request;
+use tide::{Body, Request, Response, StatusCode};
+
+use crate::games::{Game, Notice, GAMES};
+
+mod control;
+mod games;
+
+async fn create_game(mut request: Request<()>) -> tide::Result {
+    let body = request.body_string().await?;
+    let rules = if body.trim().is_empty() {
+        HouseRules::default()
+    } else {
+        serde_json::from_str::<HouseRules>(&body)
+            .map_err(|err| tide::Error::new(StatusCode::BadRequest, err))?
+    };
 
-async fn create_game(_: Request<()>) -> tide::Result {
-    let mut game_code = String::from("/monopoly_socks/");
+    let mut game_code = String::new();
 
     loop {
         for _ in 0..8 {
             game_code.push(rand::thread_rng().gen_range(b'A'..=b'Z') as char);
         }
 
-        if !Path::new(&game_code).exists() {
+        if !GAMES.lock().contains(&game_code) && !games::socket_path(&game_code).exists() {
             break;
         }
-    }
 
-    let mut host_key = String::new();
-    for _ in 0..128 {
-        host_key.push(rand::thread_rng().gen_range(b'A'..=b'Z') as char);
+        game_code.clear();
     }
 
-    Command::new(std::env::var("MONOPOLY_GAME_BIN_PATH").unwrap())
-        .env(
-            "MONOPOLY_CHOWN_ID",
-            std::env::var("MONOPOLY_CHOWN_ID").unwrap(),
-        )
-        .env("MONOPOLY_GAME_PATH", &game_code)
-        .env("MONOPOLY_HOST_KEY", &host_key)
-        .spawn()?;
+    let host_key = games::key();
+    let control_key = games::key();
+
+    // Held until the game is registered, so its first report can't race it.
+    let mut games = GAMES.lock();
+
+    let child = games::spawn(
+        &game_code,
+        &host_key,
+        &control_key,
+        Some(&serde_json::to_string(&rules)?),
+    )?;
+
+    games.insert(&game_code, &control_key, child);
+
+    Ok(format!("{game_code}\n{host_key}").into())
+}
+
+async fn list_games(_: Request<()>) -> tide::Result {
+    let games = GAMES
+        .lock()
+        .list()
+        .into_iter()
+        .map(Game::listing)
+        .collect::<Vec<_>>();
+
+    Ok(Body::from_json(&games)?.into())
+}
+
+async fn get_game(request: Request<()>) -> tide::Result {
+    let Some(game) = GAMES.lock().get(request.param("code")?).map(Game::listing) else {
+        return Ok(Response::new(StatusCode::NotFound));
+    };
 
-    Ok(format!("{}\n{}", &game_code[16..24], host_key).into())
+    Ok(Body::from_json(&game)?.into())
+}
+
+/// Called by game servers when their player count or phase changes, when a
+/// player joins and when the game ends.
+async fn notify_game(mut request: Request<()>) -> tide::Result {
+    let notice = request.body_json::<Notice>().await?;
+
+    if GAMES.lock().notify(request.param("code")?, notice) {
+        Ok(Response::new(StatusCode::Ok))
+    } else {
+        Ok(Response::new(StatusCode::NotFound))
+    }
+}
+
+/// Forwards a control request to a game, answering with the game's reply.
+async fn control_game(mut request: Request<()>) -> tide::Result {
+    let body = request.body_json::<serde_json::Value>().await?;
+
+    match control::send(request.param("code")?, &body).await {
+        Ok(Some(reply)) => Ok(Body::from_json(&reply)?.into()),
+        Ok(None) => Ok(Response::new(StatusCode::NotFound)),
+        Err(err) => Err(tide::Error::from_str(
+            StatusCode::BadGateway,
+            err.to_string(),
+        )),
+    }
 }
 
 async fn test_sock(mut request: Request<()>) -> tide::Result {
@@ -47,10 +106,6 @@ async fn test_sock(mut request: Request<()>) -> tide::Result {
 }
 
 fn main() -> Result<()> {
-    unsafe {
-        libc::signal(libc::SIGCHLD, libc::SIG_IGN);
-    }
-
     simple_logger::SimpleLogger::new()
         .with_level(LevelFilter::Debug)
         .init()?;
@@ -68,11 +123,17 @@ fn main() -> Result<()> {
         "MISSING MONOPOLY_CHOWN_ID ENV VAR"
     );
 
+    games::remove_stale_sockets()?;
+
     async_std::task::block_on(async move {
+        async_std::task::spawn(games::reap());
+
         let task_one = async_std::task::spawn(async move {
             let mut server = tide::new();
 
             server.at("/api/create_game").post(create_game);
+            server.at("/api/games").get(list_games);
+            server.at("/api/games/:code").get(get_game);
 
             let ip_addr = format!("127.0.0.1:{}", std::env::var("MONOPOLY_HTTP_PORT")?);
             server.listen(ip_addr).await?;
@@ -84,21 +145,27 @@ fn main() -> Result<()> {
             let mut server = tide::new();
 
             server.at("/api/internal/test").get(test_sock);
+            server.at("/api/internal/games/:code").post(notify_game);
+            server
+                .at("/api/internal/games/:code/control")
+                .post(control_game);
 
             std::fs::remove_file("/monopoly_socks/host")?;
             let mut listener = server
                 .bind(UnixListener::bind("/monopoly_socks/host").await?)
                 .await?;
+
+            // Relaunched games report in over the listener bound above.
+            games::relaunch_saved()?;
             listener.accept().await?;
 
             Ok::<(), eyre::Error>(())
         });
 
         match task_one.join(task_two).await {
-            (Ok(_), Ok(_)) => (),
-            (Err(e), Ok(_)) => panic!("{}", e),
-            (Ok(_), Err(e)) => panic!("{}", e),
-            (Err(e1), Err(e2)) => panic!("{}\n{}", e1, e2),
+            (Ok(()), Ok(())) => (),
+            (Err(e), Ok(())) | (Ok(()), Err(e)) => panic!("{}", e),
+            (Err(e1), Err(e2)) => panic!("{e1}\n{e2}"),
         }
 
         Ok(())
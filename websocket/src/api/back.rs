@@ -1,11 +1,18 @@
 use std::fmt::Debug;
 use std::sync::{mpsc, Arc};
+use std::time::Instant;
 
 use async_std::os::unix::net::UnixStream;
+use log::info;
 use parking_lot::Mutex;
+use serde::Serialize;
 use soketto::Sender;
 
-use crate::game::Session;
+use crate::api::persist;
+use crate::api::wire::{Client, Protocol};
+use crate::game::auction::AuctionResult;
+use crate::game::trade::{Bundle, Trade};
+use crate::game::{Outcome, Session};
 use crate::util;
 
 #[derive(Debug, Eq, PartialEq)]
@@ -17,12 +24,16 @@ enum EventState {
 pub struct EventHandler {
     ws_id: u32,
     state: EventState,
-    recv: mpsc::Receiver<Event>,
+    recv: mpsc::Receiver<(u64, Event)>,
     game: Arc<Mutex<Session>>,
 }
 
 impl EventHandler {
-    pub fn new(ws_id: u32, recv: mpsc::Receiver<Event>, game: Arc<Mutex<Session>>) -> EventHandler {
+    pub fn new(
+        ws_id: u32,
+        recv: mpsc::Receiver<(u64, Event)>,
+        game: Arc<Mutex<Session>>,
+    ) -> EventHandler {
         EventHandler {
             ws_id,
             state: EventState::Running,
@@ -31,21 +42,19 @@ impl EventHandler {
         }
     }
 
-    pub fn pump_event(&mut self) -> Option<Box<dyn EventExt>> {
-        let Ok(event) = self.recv.recv() else {
+    /// Waits for the next event along with its sequence number.
+    pub fn pump_event(&mut self) -> Option<(u64, Event)> {
+        let Ok((seq, event)) = self.recv.recv() else {
+            info!("Event channel closed on WS (#{})", self.ws_id);
             self.state = EventState::Killed;
             return None;
         };
 
-        Some(event.into())
+        Some((seq, event))
     }
 
-    pub fn execute_event(
-        &mut self,
-        event: Box<dyn EventExt>,
-        send: Arc<Mutex<Sender<UnixStream>>>,
-    ) -> Box<dyn EventExt> {
-        event.execute(self.game.clone()).respond(send)
+    pub fn execute_event(&mut self, seq: u64, event: Event, client: &Client) {
+        send(&self.game, client, seq, event);
     }
 
     pub fn is_kill(&self) -> bool {
@@ -53,40 +62,241 @@ impl EventHandler {
     }
 }
 
+/// Writes event number `seq` to `client` in the framing it negotiated. Text
+/// frames carry it on their first line.
+pub fn send(game: &Arc<Mutex<Session>>, client: &Client, seq: u64, event: Event) {
+    match client.protocol() {
+        Protocol::Text => {
+            Box::<dyn EventExt>::from(event)
+                .execute(game.clone())
+                .respond(seq, client.sender());
+        }
+        Protocol::Json | Protocol::MsgPack => {
+            let mut fields = serde_json::to_value(&event).unwrap();
+            fields["seq"] = seq.into();
+
+            client.send(&fields);
+        }
+    }
+}
+
+/// Fans `event` out to the event handler of every player.
+pub fn broadcast(game: &Arc<Mutex<Session>>, event: &Event) {
+    game.lock().publish(event);
+}
+
+/// Sends `event` to a single player.
+pub fn notify(game: &Arc<Mutex<Session>>, player_id: usize, event: &Event) {
+    game.lock().deliver(player_id, event);
+}
+
+/// Waits out auction `id`, whose deadline moves with every bid, then closes
+/// it and announces the result. Deeds queued up for auction after a
+/// bankruptcy are put up one after another.
+pub fn watch_auction(game: &Arc<Mutex<Session>>, mut id: usize) {
+    loop {
+        let closing = persist::closing_auction();
+        let mut session = game.lock();
+
+        let Some(deadline) = session.auction_deadline(id) else {
+            return;
+        };
+
+        let now = Instant::now();
+        if now < deadline {
+            drop(session);
+            drop(closing);
+            std::thread::sleep(deadline - now);
+            continue;
+        }
+
+        let (events, next) = close_auction(&mut session);
+        drop(session);
+
+        for event in &events {
+            broadcast(game, event);
+        }
+        closing.closed();
+
+        let Some(next) = next else {
+            return;
+        };
+
+        id = next;
+    }
+}
+
+/// Closes the running auction and opens the next queued one, if any,
+/// returning the events announcing both and the id of the new auction.
+pub fn close_auction(session: &mut Session) -> (Vec<Event>, Option<usize>) {
+    let result = session.close_auction().unwrap();
+    let mut events = vec![Event::AuctionEnd(AuctionEnd::new(&result, session))];
+
+    let next = session.next_auction();
+    if next.is_some() {
+        events.push(Event::Auction(Auction::new(
+            session.auction_position().unwrap(),
+            session.auction_timeout().as_secs(),
+        )));
+    }
+
+    (events, next)
+}
+
 pub trait EventExt: Debug {
     fn execute(self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn EventExt>;
 
-    fn respond(self: Box<Self>, send: Arc<Mutex<Sender<UnixStream>>>) -> Box<dyn EventExt>;
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt>;
 
     fn is_error(&self) -> bool {
         false
     }
 }
 
-#[derive(Debug)]
+/// Serialized for JSON clients as the event's fields plus its name under
+/// `event`, matching the names used by the text protocol.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Event {
+    #[serde(rename = "CHAT")]
     Msg(Message),
+    Roll(Roll),
+    Jail(Jail),
+    Turn(Turn),
+    Offer(Offer),
+    #[serde(rename = "BUY")]
+    Purchase(Purchase),
+    Decline(Decline),
+    Rent(Rent),
+    Salary(Salary),
+    Tax(Tax),
+    Auction(Auction),
+    Bid(Bid),
+    AuctionEnd(AuctionEnd),
+    #[serde(rename = "TRADE")]
+    TradeOffer(TradeOffer),
+    TradeRejected(TradeRejected),
+    TradeDone(TradeDone),
+    Build(Build),
+    SellBuilding(SellBuilding),
+    Mortgage(Mortgage),
+    Unmortgage(Unmortgage),
+    KeepMortgage(KeepMortgage),
+    MortgageDecision(MortgageDecision),
+    #[serde(rename = "CARD")]
+    CardDrawn(CardDrawn),
+    Move(Move),
+    #[serde(rename = "PAY")]
+    Transfer(Transfer),
+    Released(Released),
+    Debt(Debt),
+    Bankrupt(Bankrupt),
+    GameOver(GameOver),
+    Ready(Ready),
+    Start(Start),
+    System(System),
 }
 
 impl From<Event> for Box<dyn EventExt> {
     fn from(event: Event) -> Self {
         match event {
             Event::Msg(msg) => Box::new(msg),
+            Event::Roll(roll) => Box::new(roll),
+            Event::Jail(jail) => Box::new(jail),
+            Event::Turn(turn) => Box::new(turn),
+            Event::Offer(offer) => Box::new(offer),
+            Event::Purchase(purchase) => Box::new(purchase),
+            Event::Decline(decline) => Box::new(decline),
+            Event::Rent(rent) => Box::new(rent),
+            Event::Salary(salary) => Box::new(salary),
+            Event::Tax(tax) => Box::new(tax),
+            Event::Auction(auction) => Box::new(auction),
+            Event::Bid(bid) => Box::new(bid),
+            Event::AuctionEnd(end) => Box::new(end),
+            Event::TradeOffer(offer) => Box::new(offer),
+            Event::TradeRejected(rejected) => Box::new(rejected),
+            Event::TradeDone(done) => Box::new(done),
+            Event::Build(build) => Box::new(build),
+            Event::SellBuilding(sell) => Box::new(sell),
+            Event::Mortgage(mortgage) => Box::new(mortgage),
+            Event::Unmortgage(unmortgage) => Box::new(unmortgage),
+            Event::KeepMortgage(keep) => Box::new(keep),
+            Event::MortgageDecision(decision) => Box::new(decision),
+            Event::CardDrawn(card) => Box::new(card),
+            Event::Move(movement) => Box::new(movement),
+            Event::Transfer(transfer) => Box::new(transfer),
+            Event::Released(released) => Box::new(released),
+            Event::Debt(debt) => Box::new(debt),
+            Event::Bankrupt(bankrupt) => Box::new(bankrupt),
+            Event::GameOver(over) => Box::new(over),
+            Event::Ready(ready) => Box::new(ready),
+            Event::Start(start) => Box::new(start),
+            Event::System(system) => Box::new(system),
+        }
+    }
+}
+
+impl Event {
+    pub fn from_outcome(outcome: &Outcome, game: &Session) -> Event {
+        let username = |id| game.player_username_by_id(id).unwrap();
+
+        match *outcome {
+            Outcome::Salary { player, amount } => {
+                Event::Salary(Salary::new(&username(player), amount))
+            }
+            Outcome::Tax { player, amount } => Event::Tax(Tax::new(&username(player), amount)),
+            Outcome::Rent {
+                payer,
+                owner,
+                amount,
+            } => Event::Rent(Rent::new(&username(payer), &username(owner), amount)),
+            Outcome::Offer { player, position } => Event::Offer(Offer::new(
+                &username(player),
+                position,
+                game.board().space(position).price().unwrap(),
+            )),
+            Outcome::Jailed { player } => Event::Jail(Jail::new(&username(player))),
+            Outcome::Released { player, how } => {
+                Event::Released(Released::new(&username(player), how.name()))
+            }
+            Outcome::Card { player, deck, text } => {
+                Event::CardDrawn(CardDrawn::new(&username(player), deck.name(), text))
+            }
+            Outcome::Move { player, position } => {
+                Event::Move(Move::new(&username(player), position))
+            }
+            Outcome::Transfer { from, to, amount } => {
+                Event::Transfer(Transfer::new(from.map(username), to.map(username), amount))
+            }
+            Outcome::Debt {
+                debtor,
+                creditor,
+                amount,
+            } => Event::Debt(Debt::new(&username(debtor), creditor.map(username), amount)),
+            Outcome::Bankrupt { player, creditor } => {
+                Event::Bankrupt(Bankrupt::new(&username(player), creditor.map(username)))
+            }
+            Outcome::Turn { player } => Event::Turn(Turn::new(&username(player))),
+            Outcome::GameOver { winner } => Event::GameOver(GameOver::new(&username(winner))),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Message {
     username: String,
     msg: String,
 }
 
 impl Message {
-    pub fn new(username: &String, msg: &String) -> Message {
+    pub fn new(username: &str, msg: &str) -> Message {
         Message {
-            username: username.clone(),
-            msg: msg.clone(),
+            username: username.to_string(),
+            msg: msg.to_string(),
         }
     }
 }
@@ -96,10 +306,1140 @@ impl EventExt for Message {
         self
     }
 
-    fn respond(self: Box<Self>, send: Arc<Mutex<Sender<UnixStream>>>) -> Box<dyn EventExt> {
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send
+            .lock()
+            .send_text(format!("{seq}\nCHAT\n{}\n{}", self.username, self.msg)))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Roll {
+    username: String,
+    dice: [u8; 2],
+    position: usize,
+}
+
+impl Roll {
+    pub fn new(username: &str, dice: [u8; 2], position: usize) -> Roll {
+        Roll {
+            username: username.to_string(),
+            dice,
+            position,
+        }
+    }
+}
+
+impl EventExt for Roll {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nROLL\n{}\n{}\n{}\n{}",
+            self.username, self.dice[0], self.dice[1], self.position
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Jail {
+    username: String,
+}
+
+impl Jail {
+    pub fn new(username: &str) -> Jail {
+        Jail {
+            username: username.to_string(),
+        }
+    }
+}
+
+impl EventExt for Jail {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send
+            .lock()
+            .send_text(format!("{seq}\nJAIL\n{}", self.username)))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Turn {
+    username: String,
+}
+
+impl Turn {
+    pub fn new(username: &str) -> Turn {
+        Turn {
+            username: username.to_string(),
+        }
+    }
+}
+
+impl EventExt for Turn {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send
+            .lock()
+            .send_text(format!("{seq}\nTURN\n{}", self.username)))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Offer {
+    username: String,
+    position: usize,
+    price: u32,
+}
+
+impl Offer {
+    pub fn new(username: &str, position: usize, price: u32) -> Offer {
+        Offer {
+            username: username.to_string(),
+            position,
+            price,
+        }
+    }
+}
+
+impl EventExt for Offer {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nOFFER\n{}\n{}\n{}",
+            self.username, self.position, self.price
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Purchase {
+    username: String,
+    position: usize,
+    price: u32,
+}
+
+impl Purchase {
+    pub fn new(username: &str, position: usize, price: u32) -> Purchase {
+        Purchase {
+            username: username.to_string(),
+            position,
+            price,
+        }
+    }
+}
+
+impl EventExt for Purchase {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nBUY\n{}\n{}\n{}",
+            self.username, self.position, self.price
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Decline {
+    username: String,
+    position: usize,
+}
+
+impl Decline {
+    pub fn new(username: &str, position: usize) -> Decline {
+        Decline {
+            username: username.to_string(),
+            position,
+        }
+    }
+}
+
+impl EventExt for Decline {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nDECLINE\n{}\n{}",
+            self.username, self.position
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Rent {
+    payer: String,
+    owner: String,
+    amount: u32,
+}
+
+impl Rent {
+    pub fn new(payer: &str, owner: &str, amount: u32) -> Rent {
+        Rent {
+            payer: payer.to_string(),
+            owner: owner.to_string(),
+            amount,
+        }
+    }
+}
+
+impl EventExt for Rent {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nRENT\n{}\n{}\n{}",
+            self.payer, self.owner, self.amount
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Salary {
+    username: String,
+    amount: u32,
+}
+
+impl Salary {
+    pub fn new(username: &str, amount: u32) -> Salary {
+        Salary {
+            username: username.to_string(),
+            amount,
+        }
+    }
+}
+
+impl EventExt for Salary {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send
+            .lock()
+            .send_text(format!("{seq}\nSALARY\n{}\n{}", self.username, self.amount)))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Tax {
+    username: String,
+    amount: u32,
+}
+
+impl Tax {
+    pub fn new(username: &str, amount: u32) -> Tax {
+        Tax {
+            username: username.to_string(),
+            amount,
+        }
+    }
+}
+
+impl EventExt for Tax {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send
+            .lock()
+            .send_text(format!("{seq}\nTAX\n{}\n{}", self.username, self.amount)))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Auction {
+    position: usize,
+    timeout: u64,
+}
+
+impl Auction {
+    pub fn new(position: usize, timeout: u64) -> Auction {
+        Auction { position, timeout }
+    }
+}
+
+impl EventExt for Auction {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nAUCTION\n{}\n{}",
+            self.position, self.timeout
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Bid {
+    username: String,
+    amount: u32,
+}
+
+impl Bid {
+    pub fn new(username: &str, amount: u32) -> Bid {
+        Bid {
+            username: username.to_string(),
+            amount,
+        }
+    }
+}
+
+impl EventExt for Bid {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
         util::sync!(send
             .lock()
-            .send_text(format!("0\nCHAT\n{}\n{}", self.username, self.msg)))
+            .send_text(format!("{seq}\nBID\n{}\n{}", self.username, self.amount)))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuctionEnd {
+    position: usize,
+    winner: Option<(String, u32)>,
+}
+
+impl AuctionEnd {
+    pub fn new(result: &AuctionResult, game: &Session) -> AuctionEnd {
+        AuctionEnd {
+            position: result.position,
+            winner: result
+                .winner
+                .map(|(winner, amount)| (game.player_username_by_id(winner).unwrap(), amount)),
+        }
+    }
+}
+
+impl EventExt for AuctionEnd {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        let text = match &self.winner {
+            Some((username, amount)) => {
+                format!(
+                    "{seq}\nAUCTION_END\n{}\n{}\n{}",
+                    self.position, username, amount
+                )
+            }
+            None => format!("{seq}\nAUCTION_END\n{}", self.position),
+        };
+
+        util::sync!(send.lock().send_text(text)).unwrap();
+
+        self
+    }
+}
+
+fn bundle_lines(bundle: &Bundle) -> String {
+    let deeds = bundle
+        .deeds
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!("{}\n{}\n{}", bundle.cash, deeds, bundle.jail_cards)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeOffer {
+    id: usize,
+    proposer: String,
+    target: String,
+    offer: Bundle,
+    request: Bundle,
+}
+
+impl TradeOffer {
+    pub fn new(trade: &Trade, game: &Session) -> TradeOffer {
+        TradeOffer {
+            id: trade.id,
+            proposer: game.player_username_by_id(trade.proposer).unwrap(),
+            target: game.player_username_by_id(trade.target).unwrap(),
+            offer: trade.offer.clone(),
+            request: trade.request.clone(),
+        }
+    }
+}
+
+impl EventExt for TradeOffer {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nTRADE\n{}\n{}\n{}\n{}\n{}",
+            self.id,
+            self.proposer,
+            self.target,
+            bundle_lines(&self.offer),
+            bundle_lines(&self.request)
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeRejected {
+    id: usize,
+    username: String,
+}
+
+impl TradeRejected {
+    pub fn new(id: usize, username: &str) -> TradeRejected {
+        TradeRejected {
+            id,
+            username: username.to_string(),
+        }
+    }
+}
+
+impl EventExt for TradeRejected {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nTRADE_REJECTED\n{}\n{}",
+            self.id, self.username
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeDone {
+    id: usize,
+    proposer: String,
+    target: String,
+    offer: Bundle,
+    request: Bundle,
+}
+
+impl TradeDone {
+    pub fn new(trade: &Trade, game: &Session) -> TradeDone {
+        TradeDone {
+            id: trade.id,
+            proposer: game.player_username_by_id(trade.proposer).unwrap(),
+            target: game.player_username_by_id(trade.target).unwrap(),
+            offer: trade.offer.clone(),
+            request: trade.request.clone(),
+        }
+    }
+}
+
+impl EventExt for TradeDone {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nTRADE_DONE\n{}\n{}\n{}\n{}",
+            self.proposer,
+            self.target,
+            bundle_lines(&self.offer),
+            bundle_lines(&self.request)
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Build {
+    username: String,
+    position: usize,
+    buildings: u8,
+}
+
+impl Build {
+    pub fn new(username: &str, position: usize, buildings: u8) -> Build {
+        Build {
+            username: username.to_string(),
+            position,
+            buildings,
+        }
+    }
+}
+
+impl EventExt for Build {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nBUILD\n{}\n{}\n{}",
+            self.username, self.position, self.buildings
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SellBuilding {
+    username: String,
+    position: usize,
+    buildings: u8,
+}
+
+impl SellBuilding {
+    pub fn new(username: &str, position: usize, buildings: u8) -> SellBuilding {
+        SellBuilding {
+            username: username.to_string(),
+            position,
+            buildings,
+        }
+    }
+}
+
+impl EventExt for SellBuilding {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nSELL_BUILDING\n{}\n{}\n{}",
+            self.username, self.position, self.buildings
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Mortgage {
+    username: String,
+    position: usize,
+}
+
+impl Mortgage {
+    pub fn new(username: &str, position: usize) -> Mortgage {
+        Mortgage {
+            username: username.to_string(),
+            position,
+        }
+    }
+}
+
+impl EventExt for Mortgage {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nMORTGAGE\n{}\n{}",
+            self.username, self.position
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Unmortgage {
+    username: String,
+    position: usize,
+}
+
+impl Unmortgage {
+    pub fn new(username: &str, position: usize) -> Unmortgage {
+        Unmortgage {
+            username: username.to_string(),
+            position,
+        }
+    }
+}
+
+impl EventExt for Unmortgage {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nUNMORTGAGE\n{}\n{}",
+            self.username, self.position
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KeepMortgage {
+    username: String,
+    position: usize,
+}
+
+impl KeepMortgage {
+    pub fn new(username: &str, position: usize) -> KeepMortgage {
+        KeepMortgage {
+            username: username.to_string(),
+            position,
+        }
+    }
+}
+
+impl EventExt for KeepMortgage {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nKEEP_MORTGAGE\n{}\n{}",
+            self.username, self.position
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MortgageDecision {
+    position: usize,
+    interest: u32,
+    unmortgage: u32,
+}
+
+impl MortgageDecision {
+    pub fn new(position: usize, interest: u32, unmortgage: u32) -> MortgageDecision {
+        MortgageDecision {
+            position,
+            interest,
+            unmortgage,
+        }
+    }
+}
+
+impl EventExt for MortgageDecision {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nMORTGAGE_DECISION\n{}\n{}\n{}",
+            self.position, self.interest, self.unmortgage
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CardDrawn {
+    username: String,
+    deck: &'static str,
+    text: &'static str,
+}
+
+impl CardDrawn {
+    pub fn new(username: &str, deck: &'static str, text: &'static str) -> CardDrawn {
+        CardDrawn {
+            username: username.to_string(),
+            deck,
+            text,
+        }
+    }
+}
+
+impl EventExt for CardDrawn {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nCARD\n{}\n{}\n{}",
+            self.username, self.deck, self.text
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Move {
+    username: String,
+    position: usize,
+}
+
+impl Move {
+    pub fn new(username: &str, position: usize) -> Move {
+        Move {
+            username: username.to_string(),
+            position,
+        }
+    }
+}
+
+impl EventExt for Move {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send
+            .lock()
+            .send_text(format!("{seq}\nMOVE\n{}\n{}", self.username, self.position)))
+        .unwrap();
+
+        self
+    }
+}
+
+/// Cash changing hands; a missing from or to is the bank.
+#[derive(Debug, Clone, Serialize)]
+pub struct Transfer {
+    from: Option<String>,
+    to: Option<String>,
+    amount: u32,
+}
+
+impl Transfer {
+    pub fn new(from: Option<String>, to: Option<String>, amount: u32) -> Transfer {
+        Transfer { from, to, amount }
+    }
+}
+
+impl EventExt for Transfer {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nPAY\n{}\n{}\n{}",
+            self.from.as_deref().unwrap_or("BANK"),
+            self.to.as_deref().unwrap_or("BANK"),
+            self.amount
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Released {
+    username: String,
+    how: &'static str,
+}
+
+impl Released {
+    pub fn new(username: &str, how: &'static str) -> Released {
+        Released {
+            username: username.to_string(),
+            how,
+        }
+    }
+}
+
+impl EventExt for Released {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send
+            .lock()
+            .send_text(format!("{seq}\nRELEASED\n{}\n{}", self.username, self.how)))
+        .unwrap();
+
+        self
+    }
+}
+
+/// Money a player cannot cover yet; a missing creditor is the bank.
+#[derive(Debug, Clone, Serialize)]
+pub struct Debt {
+    debtor: String,
+    creditor: Option<String>,
+    amount: u32,
+}
+
+impl Debt {
+    pub fn new(debtor: &str, creditor: Option<String>, amount: u32) -> Debt {
+        Debt {
+            debtor: debtor.to_string(),
+            creditor,
+            amount,
+        }
+    }
+}
+
+impl EventExt for Debt {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nDEBT\n{}\n{}\n{}",
+            self.debtor,
+            self.creditor.as_deref().unwrap_or("BANK"),
+            self.amount
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Bankrupt {
+    username: String,
+    creditor: Option<String>,
+}
+
+impl Bankrupt {
+    pub fn new(username: &str, creditor: Option<String>) -> Bankrupt {
+        Bankrupt {
+            username: username.to_string(),
+            creditor,
+        }
+    }
+}
+
+impl EventExt for Bankrupt {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nBANKRUPT\n{}\n{}",
+            self.username,
+            self.creditor.as_deref().unwrap_or("BANK")
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GameOver {
+    winner: String,
+}
+
+impl GameOver {
+    pub fn new(winner: &str) -> GameOver {
+        GameOver {
+            winner: winner.to_string(),
+        }
+    }
+}
+
+impl EventExt for GameOver {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send
+            .lock()
+            .send_text(format!("{seq}\nGAME_OVER\n{}", self.winner)))
+        .unwrap();
+
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Ready {
+    username: String,
+    ready: bool,
+}
+
+impl Ready {
+    pub fn new(username: &str, ready: bool) -> Ready {
+        Ready {
+            username: username.to_string(),
+            ready,
+        }
+    }
+}
+
+impl EventExt for Ready {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send
+            .lock()
+            .send_text(format!("{seq}\nREADY\n{}\n{}", self.username, self.ready)))
+        .unwrap();
+
+        self
+    }
+}
+
+/// The game leaving the lobby; players are listed one per line in turn order.
+#[derive(Debug, Clone, Serialize)]
+pub struct Start {
+    cash: u32,
+    order: Vec<String>,
+}
+
+impl Start {
+    pub fn new(cash: u32, order: Vec<String>) -> Start {
+        Start { cash, order }
+    }
+}
+
+impl EventExt for Start {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nSTART\n{}\n{}",
+            self.cash,
+            self.order.join("\n")
+        )))
+        .unwrap();
+
+        self
+    }
+}
+
+/// A moderation action taken by the host, followed by its arguments one per line.
+#[derive(Debug, Clone, Serialize)]
+pub struct System {
+    action: &'static str,
+    args: Vec<String>,
+}
+
+impl System {
+    pub fn new(action: &'static str, args: Vec<String>) -> System {
+        System { action, args }
+    }
+}
+
+impl EventExt for System {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn EventExt> {
+        self
+    }
+
+    fn respond(
+        self: Box<Self>,
+        seq: u64,
+        send: Arc<Mutex<Sender<UnixStream>>>,
+    ) -> Box<dyn EventExt> {
+        util::sync!(send.lock().send_text(format!(
+            "{seq}\nSYSTEM\n{}\n{}",
+            self.action,
+            self.args.join("\n")
+        )))
         .unwrap();
 
         self
@@ -0,0 +1,201 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_std::os::unix::net::UnixStream;
+use eyre::Result;
+use log::{error, info};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use soketto::{Receiver, Sender};
+
+use crate::api::back::{self, Event, System};
+use crate::api::{internal, persist};
+use crate::game::error::GameError;
+use crate::game::rules::HouseRules;
+use crate::game::Session;
+
+/// Requests from the HTTP server, sent as JSON with their kind under `control`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "control", rename_all = "snake_case")]
+enum Control {
+    Shutdown,
+    /// A notice from the operators, shown to every player.
+    Broadcast {
+        message: String,
+    },
+    Configure {
+        rules: Option<HouseRules>,
+        /// Seconds, applying to auctions started from now on.
+        auction_timeout: Option<u64>,
+    },
+}
+
+/// Whether a websocket request for `path` is the HTTP server opening the
+/// control channel. It proves itself with the key it spawned the game with.
+pub fn is_control(path: &str) -> bool {
+    std::env::var("MONOPOLY_CONTROL_KEY")
+        .is_ok_and(|key| path.strip_prefix("/internal/") == Some(key.as_str()))
+}
+
+/// Writes the game to `{code}.json` under `MONOPOLY_ARCHIVE_DIR`, returning
+/// whether there was a directory to write it to.
+fn archive(game: &Arc<Mutex<Session>>) -> Result<bool> {
+    let Ok(dir) = std::env::var("MONOPOLY_ARCHIVE_DIR") else {
+        return Ok(false);
+    };
+
+    let path = Path::new(&dir).join(format!("{}.json", internal::game_code()));
+    let archive = serde_json::to_string(&game.lock().archive())?;
+    std::fs::write(&path, archive)?;
+    info!("Archived game to {}", path.display());
+
+    Ok(true)
+}
+
+/// Archives the game and removes its socket ahead of the process exiting.
+/// The saved state is only deleted once the archive holds the game; without
+/// one it is kept, so the game is not lost.
+pub fn shut_down(game: &Arc<Mutex<Session>>) -> Result<()> {
+    // A failed archive is not worth a non-zero exit, which would have the
+    // game relaunched only to shut down again.
+    let archived = archive(game).unwrap_or_else(|err| {
+        error!("Failed to archive game, keeping its saved state: {}", err);
+        false
+    });
+
+    std::fs::remove_file(std::env::var("MONOPOLY_GAME_PATH")?)?;
+
+    if archived {
+        persist::remove()?;
+    }
+
+    Ok(())
+}
+
+fn apply(game: &Arc<Mutex<Session>>, control: Control) -> Result<(), GameError> {
+    match control {
+        Control::Shutdown => {
+            info!("Shutting down on request");
+
+            // The process exits once `serve` has sent the reply.
+            if let Err(err) = shut_down(game) {
+                error!("Failed to shut down cleanly: {}", err);
+            }
+        }
+        Control::Broadcast { message } => {
+            back::broadcast(
+                game,
+                &Event::System(System::new("BROADCAST", vec![message])),
+            );
+        }
+        Control::Configure {
+            rules,
+            auction_timeout,
+        } => {
+            {
+                let mut session = game.lock();
+
+                if let Some(rules) = rules {
+                    session.set_rules(rules)?;
+                }
+
+                if let Some(timeout) = auction_timeout {
+                    session.set_auction_timeout(Duration::from_secs(timeout));
+                }
+            }
+
+            // Settings are not in the command log, so save them right away.
+            if let Err(err) = persist::snapshot(game) {
+                error!("Failed to snapshot game: {}", err);
+            }
+        }
+    }
+
+    internal::report(game);
+
+    Ok(())
+}
+
+fn reply(result: Result<(), GameError>) -> Value {
+    match result {
+        Ok(()) => json!({ "status": "SUCCESS" }),
+        Err(error) => json!({
+            "status": "ERROR",
+            "error": error.code(),
+            "name": error.name(),
+            "message": error.message(),
+        }),
+    }
+}
+
+/// Answers control requests, one JSON text frame each, until the HTTP server
+/// hangs up.
+pub async fn serve(
+    mut sender: Sender<UnixStream>,
+    mut receiver: Receiver<UnixStream>,
+    game: Arc<Mutex<Session>>,
+) {
+    let mut data = vec![];
+
+    loop {
+        data.clear();
+
+        if receiver.receive_data(&mut data).await.is_err() {
+            return;
+        }
+
+        let control = serde_json::from_slice::<Control>(&data).map_err(|_| GameError::Malformed);
+        let exit = matches!(control, Ok(Control::Shutdown));
+        let result = control.and_then(|control| apply(&game, control));
+
+        if sender.send_text(reply(result).to_string()).await.is_err() {
+            return;
+        }
+
+        if exit {
+            sender.close().await.ok();
+            std::process::exit(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn controls_are_tagged_by_kind() {
+        let control = serde_json::from_str::<Control>(
+            r#"{"control": "configure", "rules": {"auctions": false}}"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            control,
+            Control::Configure {
+                rules: Some(HouseRules {
+                    auctions: false,
+                    ..
+                }),
+                auction_timeout: None,
+            }
+        ));
+        assert!(serde_json::from_str::<Control>(r#"{"control": "restart"}"#).is_err());
+    }
+
+    #[test]
+    fn errors_are_replied_with_their_code() {
+        assert_eq!(reply(Ok(())), json!({ "status": "SUCCESS" }));
+        assert_eq!(
+            reply(Err(GameError::AlreadyStarted)),
+            json!({
+                "status": "ERROR",
+                "error": 41,
+                "name": "ALREADY_STARTED",
+                "message": "The game has already started.",
+            })
+        );
+    }
+}
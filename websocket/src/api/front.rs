@@ -1,3 +1,5 @@
+#![allow(clippy::new_ret_no_self)]
+
 use std::any::Any;
 use std::fmt::Debug;
 use std::sync::{mpsc, Arc};
@@ -6,10 +8,16 @@ use async_std::io::{ReadExt, WriteExt};
 use async_std::os::unix::net::UnixStream;
 use log::{error, info};
 use parking_lot::Mutex;
+use serde_json::{json, Value};
 use soketto::{Receiver, Sender};
 
-use crate::api::back::{Event, Message};
-use crate::game::Session;
+use crate::api::back::{self, Event, Message};
+use crate::api::wire::{Action, Client, Protocol, Request};
+use crate::api::{internal, persist};
+use crate::game::board::BOARD_SIZE;
+use crate::game::error::GameError;
+use crate::game::trade::{Bundle, Trade};
+use crate::game::{Outcome, Session};
 use crate::util;
 
 #[derive(Eq, PartialEq)]
@@ -23,13 +31,13 @@ pub struct CommandHandler {
     ws_id: u32,
     player_id: usize,
     state: CommandState,
-    send: mpsc::Sender<Event>,
+    send: mpsc::Sender<(u64, Event)>,
     game: Arc<Mutex<Session>>,
     data: Vec<u8>,
 }
 
 impl CommandHandler {
-    pub fn new(ws_id: u32, send: mpsc::Sender<Event>, game: Arc<Mutex<Session>>) -> Self {
+    pub fn new(ws_id: u32, send: mpsc::Sender<(u64, Event)>, game: Arc<Mutex<Session>>) -> Self {
         CommandHandler {
             ws_id,
             player_id: 0,
@@ -40,50 +48,94 @@ impl CommandHandler {
         }
     }
 
-    pub fn pump_command(&mut self, recv: Arc<Mutex<Receiver<UnixStream>>>) -> Option<String> {
+    /// Waits for the next frame, dropping those of the wrong kind for the
+    /// negotiated protocol.
+    pub fn pump_command(
+        &mut self,
+        recv: &Mutex<Receiver<UnixStream>>,
+        protocol: Protocol,
+    ) -> Option<Vec<u8>> {
         let Ok(data_type) = util::sync!(recv.lock().receive_data(&mut self.data)) else {
             error!("Receiver closed prematurely on WS (#{})", self.ws_id);
+
+            if self.state == CommandState::Running {
+                self.game.lock().disconnect(self.player_id);
+            }
+
             self.state = CommandState::Killed;
             return None;
         };
 
-        if data_type.is_text() {
-            let Ok(data) = std::str::from_utf8(&self.data).map(str::to_string) else {
-                error!("Received invalid UTF-8 bytes on WS (#{})", self.ws_id);
-                return None;
-            };
+        let data = std::mem::take(&mut self.data);
 
-            self.data.clear();
-            Some(data)
-        } else {
-            self.data.clear();
-            None
+        if data_type.is_binary() != protocol.is_binary() {
+            error!(
+                "Received {:?} frame on {:?} WS (#{})",
+                data_type, protocol, self.ws_id
+            );
+            return None;
         }
+
+        Some(data)
     }
 
-    pub fn execute_command(
-        &mut self,
-        data: &str,
-        send: Arc<Mutex<Sender<UnixStream>>>,
-    ) -> Box<dyn CommandExt> {
-        let command = Command::new(data, self.player_id);
+    pub fn execute_command(&mut self, data: &[u8], client: &Client) -> Box<dyn CommandExt> {
+        let request = client.protocol().parse(data);
+        let command = match &request {
+            Ok(request) => Command::new(request, self.player_id),
+            Err(rejected) => Error::new(&rejected.nonce, rejected.error),
+        };
 
         info!("PROCESSING: {:#?}", command);
 
         if ((self.state == CommandState::AwaitingInit) != command.is_init()) && !command.is_error()
         {
-            return Error::new(&command.nonce(), "8".into())
+            return Error::new(&command.nonce(), GameError::InitState)
+                .execute(self.game.clone())
+                .respond(client);
+        }
+
+        if !command.is_error()
+            && !command.is_init()
+            && !command.allowed_in_lobby()
+            && !self.game.lock().started()
+        {
+            return Error::new(&command.nonce(), GameError::LobbyOpen)
+                .execute(self.game.clone())
+                .respond(client);
+        }
+
+        if !command.is_error()
+            && !command.is_init()
+            && !command.allowed_in_lobby()
+            && self.game.lock().winner().is_some()
+        {
+            return Error::new(&command.nonce(), GameError::GameFinished)
+                .execute(self.game.clone())
+                .respond(client);
+        }
+
+        if !command.is_error()
+            && !command.is_init()
+            && !command.allowed_in_debt()
+            && self.game.lock().in_debt(self.player_id)
+        {
+            return Error::new(&command.nonce(), GameError::InDebt)
                 .execute(self.game.clone())
-                .respond(send);
+                .respond(client);
         }
 
-        let command = command.execute(self.game.clone()).respond(send);
+        let command = match request.ok().filter(|_| !command.read_only()) {
+            Some(request) => persist::record(&self.game, self.player_id, request, command),
+            None => command.execute(self.game.clone()),
+        }
+        .respond(client);
 
         if command.is_error() {
             error!(
-                "Command {} completed with error code: {}",
+                "Command {} completed with error: {}",
                 command.nonce(),
-                command.error_code().unwrap(),
+                command.error().unwrap(),
             );
         } else {
             info!(
@@ -93,22 +145,34 @@ impl CommandHandler {
             );
 
             if command.is_init() {
-                self.player_id = command.as_any().downcast_ref::<Init>().unwrap().player_id;
+                let any = command.as_any();
+                self.player_id = match any.downcast_ref::<Init>() {
+                    Some(init) => {
+                        internal::joined(&init.username);
+                        init.player_id
+                    }
+                    None => any.downcast_ref::<Resume>().unwrap().player_id,
+                };
                 self.state = CommandState::Running;
-            } else if command.is_chat() {
-                let chat = command.as_any().downcast_ref::<Chat>().unwrap();
+                self.game
+                    .lock()
+                    .subscribe(self.player_id, self.send.clone());
+            }
+
+            for event in command.events() {
+                back::broadcast(&self.game, &event);
+            }
+
+            for (player_id, event) in command.notifications() {
+                back::notify(&self.game, player_id, &event);
+            }
 
-                self.send
-                    .send(Event::Msg(Message::new(
-                        &self
-                            .game
-                            .lock()
-                            .player_username_by_id(chat.player_id)
-                            .unwrap(),
-                        &chat.msg,
-                    )))
-                    .unwrap();
+            if let Some(auction) = command.auction() {
+                let game = self.game.clone();
+                std::thread::spawn(move || back::watch_auction(&game, auction));
             }
+
+            internal::report(&self.game);
         }
 
         command
@@ -119,10 +183,34 @@ impl CommandHandler {
     }
 }
 
+/// Replays a logged command from `player_id` against a restored game,
+/// holding its events for players until they reconnect. Auctions it opens
+/// are timed by `persist::restore` once the whole log is replayed.
+pub fn replay(game: &Arc<Mutex<Session>>, player_id: usize, request: &Request) {
+    let command = Command::new(request, player_id).execute(game.clone());
+
+    if command.is_error() {
+        error!(
+            "Replayed command {} failed: {}",
+            command.nonce(),
+            command.error().unwrap(),
+        );
+        return;
+    }
+
+    for event in command.events() {
+        back::broadcast(game, &event);
+    }
+
+    for (player_id, event) in command.notifications() {
+        back::notify(game, player_id, &event);
+    }
+}
+
 pub trait CommandExt: Debug {
     fn execute(self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt>;
 
-    fn respond(self: Box<Self>, sender: Arc<Mutex<Sender<UnixStream>>>) -> Box<dyn CommandExt>;
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt>;
 
     fn nonce(&self) -> String;
 
@@ -130,44 +218,122 @@ pub trait CommandExt: Debug {
         false
     }
 
-    fn is_chat(&self) -> bool {
+    fn is_error(&self) -> bool {
         false
     }
 
-    fn is_error(&self) -> bool {
-        false
+    fn error(&self) -> Option<GameError> {
+        None
+    }
+
+    /// Events to broadcast to every connected player once the command succeeds.
+    fn events(&self) -> Vec<Event> {
+        vec![]
+    }
+
+    /// Events meant only for the given players once the command succeeds.
+    fn notifications(&self) -> Vec<(usize, Event)> {
+        vec![]
     }
 
-    fn error_code(&self) -> Option<String> {
+    /// Auction the command opened, to be closed by a timer once it runs out.
+    /// Replayed commands leave that to `persist::restore`.
+    fn auction(&self) -> Option<usize> {
         None
     }
 
+    /// Whether the command may be issued while no game is in progress, i.e.
+    /// before the host starts it or after it has been won.
+    fn allowed_in_lobby(&self) -> bool {
+        false
+    }
+
+    /// Whether a player who owes money may still issue the command, i.e. it
+    /// helps raise funds or settle the debt.
+    fn allowed_in_debt(&self) -> bool {
+        false
+    }
+
+    /// Whether the command leaves the saved game as it was, so it need not
+    /// be logged for restoring after a crash.
+    fn read_only(&self) -> bool {
+        false
+    }
+
     fn as_any(&self) -> &dyn Any;
 }
 
 struct Command {}
 
 impl Command {
-    fn new(data: &str, player_id: usize) -> Box<dyn CommandExt> {
-        let mut request = data.lines();
-
-        let Some(nonce) = request.next().map(str::to_string) else {
-            return Error::new(&String::from("0"), "0".into());
-        };
-
-        let Some(command) = request.next() else {
-            return Error::new(&nonce, "0".into());
-        };
-
-        match command {
-            "INIT" => Init::new(&nonce, &mut request),
-            "ECHO" => Echo::new(&nonce, &mut request),
-            "CHAT" => Chat::new(&nonce, &mut request, player_id),
-            _ => Error::new(&nonce, "0".into()),
+    fn new(request: &Request, player_id: usize) -> Box<dyn CommandExt> {
+        let nonce = request.nonce.as_str();
+
+        match request.action.clone() {
+            Action::Init { username, host_key } => Init::new(nonce, username, host_key),
+            Action::Resume { token } => Resume::new(nonce, token),
+            Action::State => State::new(nonce, player_id),
+            Action::Errors => Errors::new(nonce),
+            Action::Sync { since } => Resync::new(nonce, since, player_id),
+            Action::Echo { msg } => Echo::new(nonce, msg),
+            Action::Chat { msg } => Chat::new(nonce, msg, player_id),
+            Action::Roll => Roll::new(nonce, player_id),
+            Action::EndTurn => EndTurn::new(nonce, player_id),
+            Action::Buy => Buy::new(nonce, player_id),
+            Action::Decline => Decline::new(nonce, player_id),
+            Action::Bid { amount } => Bid::new(nonce, amount, player_id),
+            Action::TradePropose {
+                target,
+                offer,
+                request,
+            } => TradePropose::new(nonce, target, offer, request, player_id),
+            Action::TradeAccept { id } => TradeAccept::new(nonce, id, player_id),
+            Action::TradeReject { id } => TradeReject::new(nonce, id, player_id),
+            Action::TradeCounter { id, offer, request } => {
+                TradeCounter::new(nonce, id, offer, request, player_id)
+            }
+            Action::Build { position } => Build::new(nonce, position, player_id),
+            Action::SellBuilding { position } => SellBuilding::new(nonce, position, player_id),
+            Action::Mortgage { position } => Mortgage::new(nonce, position, player_id),
+            Action::Unmortgage { position } => Unmortgage::new(nonce, position, player_id),
+            Action::KeepMortgage { position } => KeepMortgage::new(nonce, position, player_id),
+            Action::PayBail => PayBail::new(nonce, player_id),
+            Action::UseJailCard => UseJailCard::new(nonce, player_id),
+            Action::Ready => Ready::new(nonce, player_id),
+            Action::Start => Start::new(nonce, player_id),
+            Action::Kick { target } => Kick::new(nonce, target, player_id),
+            Action::Mute { target } => Mute::new(nonce, target, player_id),
+            Action::TransferHost { target } => TransferHost::new(nonce, target, player_id),
+            Action::PayDebt => PayDebt::new(nonce, player_id),
+            Action::Bankrupt => Bankrupt::new(nonce, player_id),
         }
     }
 }
 
+/// Prompts each of `players` about mortgaged deeds they have just received.
+fn mortgage_decisions(game: &Session, players: &[usize]) -> Vec<(usize, Event)> {
+    players
+        .iter()
+        .flat_map(|player_id| {
+            game.mortgage_decisions(*player_id)
+                .into_iter()
+                .map(|position| {
+                    let value = game.board().space(position).mortgage_value().unwrap();
+                    let interest = Session::interest(value);
+
+                    (
+                        *player_id,
+                        Event::MortgageDecision(back::MortgageDecision::new(
+                            position,
+                            interest,
+                            value + interest,
+                        )),
+                    )
+                })
+        })
+        .collect()
+}
+
 #[derive(Debug, Default)]
 struct Init {
     nonce: String,
@@ -178,17 +344,11 @@ struct Init {
 }
 
 impl Init {
-    fn new(nonce: &String, request: &mut std::str::Lines<'_>) -> Box<dyn CommandExt> {
+    fn new(nonce: &str, username: String, host_key: Option<String>) -> Box<dyn CommandExt> {
         Box::new(Init {
-            nonce: nonce.clone(),
-            username: {
-                let Some(username) = request.next().map(str::to_string) else {
-                    return Error::new(&nonce, "1".into());
-                };
-
-                username
-            },
-            host_key: request.next().map(str::to_string),
+            nonce: nonce.to_string(),
+            username,
+            host_key,
             player_id: 0,
             game: None,
         })
@@ -200,22 +360,89 @@ impl CommandExt for Init {
         self.game = Some(game.clone());
         let mut game = game.lock();
 
-        match game.add_player(&self.username, &self.host_key) {
+        match game.add_player(&self.username, self.host_key.as_ref()) {
             Ok(id) => {
                 self.player_id = id;
 
                 self as Box<dyn CommandExt>
             }
-            Err(err) => Error::new(&self.nonce, err.to_string()),
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        let binding = self.game.as_ref().unwrap().clone();
+        let mut game = binding.lock();
+        game.assoc_sock(self.player_id, client.sender());
+
+        let token = game.resume_token(self.player_id);
+        client.reply(
+            format!("{}\nSUCCESS\n{}", self.nonce, token),
+            &json!({ "nonce": self.nonce, "status": "SUCCESS", "token": token }),
+        );
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn is_init(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Takes over the seat of a player whose connection dropped, using the
+/// token they were handed on `INIT`.
+#[derive(Debug, Default)]
+struct Resume {
+    nonce: String,
+    token: String,
+    player_id: usize,
+    game: Option<Arc<Mutex<Session>>>,
+}
+
+impl Resume {
+    fn new(nonce: &str, token: String) -> Box<dyn CommandExt> {
+        Box::new(Resume {
+            nonce: nonce.to_string(),
+            token,
+            player_id: 0,
+            game: None,
+        })
+    }
+}
+
+impl CommandExt for Resume {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        self.game = Some(game.clone());
+        let mut game = game.lock();
+
+        match game.resume(&self.token) {
+            Ok(id) => {
+                self.player_id = id;
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
         }
     }
 
-    fn respond(self: Box<Self>, sender: Arc<Mutex<Sender<UnixStream>>>) -> Box<dyn CommandExt> {
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
         let binding = self.game.as_ref().unwrap().clone();
         let mut game = binding.lock();
-        game.assoc_sock(self.player_id, sender.clone());
+        game.assoc_sock(self.player_id, client.sender());
 
-        util::sync!(sender.lock().send_text(format!("{}\nSUCCESS", self.nonce))).unwrap();
+        let username = game.player_username_by_id(self.player_id).unwrap();
+        client.reply(
+            format!("{}\nSUCCESS\n{}", self.nonce, username),
+            &json!({ "nonce": self.nonce, "status": "SUCCESS", "username": username }),
+        );
 
         self
     }
@@ -228,6 +455,10 @@ impl CommandExt for Init {
         true
     }
 
+    fn read_only(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -241,16 +472,10 @@ struct Echo {
 }
 
 impl Echo {
-    fn new(nonce: &String, request: &mut std::str::Lines<'_>) -> Box<dyn CommandExt> {
+    fn new(nonce: &str, msg: String) -> Box<dyn CommandExt> {
         Box::new(Echo {
-            nonce: nonce.clone(),
-            msg: {
-                let Some(msg) = request.next().map(str::to_string) else {
-                    return Error::new(&nonce.clone(), "3".into());
-                };
-
-                msg
-            },
+            nonce: nonce.to_string(),
+            msg,
             resp: String::new(),
         })
     }
@@ -258,9 +483,8 @@ impl Echo {
 
 impl CommandExt for Echo {
     fn execute(mut self: Box<Echo>, _: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
-        let mut stream = match util::sync!(UnixStream::connect("/monopoly_socks/host")) {
-            Ok(stream) => stream,
-            Err(_) => return Error::new(&self.nonce, "4".into()),
+        let Ok(mut stream) = util::sync!(UnixStream::connect("/monopoly_socks/host")) else {
+            return Error::new(&self.nonce, GameError::EchoConnect);
         };
 
         let request = format!(
@@ -269,12 +493,12 @@ impl CommandExt for Echo {
             self.msg
         );
 
-        let Ok(_) = util::sync!(stream.write_all(request.as_bytes())) else {
-            return Error::new(&self.nonce, "5".into());
+        let Ok(()) = util::sync!(stream.write_all(request.as_bytes())) else {
+            return Error::new(&self.nonce, GameError::EchoWrite);
         };
 
         let Ok(_) = util::sync!(stream.read_to_string(&mut self.resp)) else {
-            return Error::new(&self.nonce, "6".into());
+            return Error::new(&self.nonce, GameError::EchoRead);
         };
 
         info!("RESULT OF ECHO OPERATION:\n{}", self.resp);
@@ -282,8 +506,11 @@ impl CommandExt for Echo {
         self
     }
 
-    fn respond(self: Box<Self>, sender: Arc<Mutex<Sender<UnixStream>>>) -> Box<dyn CommandExt> {
-        util::sync!(sender.lock().send_text(&self.resp)).unwrap();
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.reply(
+            self.resp.clone(),
+            &json!({ "nonce": self.nonce, "status": "SUCCESS", "response": self.resp }),
+        );
 
         self
     }
@@ -292,56 +519,54 @@ impl CommandExt for Echo {
         self.nonce.clone()
     }
 
+    fn allowed_in_lobby(&self) -> bool {
+        true
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
 
+/// Full snapshot of the game as JSON, for clients that just joined or resumed.
 #[derive(Debug, Default)]
-struct Chat {
+struct State {
     nonce: String,
-    msg: String,
     player_id: usize,
+    snapshot: Value,
 }
 
-impl Chat {
-    pub fn new(
-        nonce: &String,
-        request: &mut std::str::Lines<'_>,
-        player_id: usize,
-    ) -> Box<dyn CommandExt> {
-        Box::new(Chat {
-            nonce: nonce.clone(),
-            msg: {
-                let msg = request.collect::<String>();
-
-                if msg.is_empty() {
-                    return Error::new(&nonce.clone(), "10".into());
-                };
-
-                if msg.len() > 12 {
-                    return Error::new(&nonce.clone(), "9".into());
-                }
-
-                msg.to_string()
-            },
+impl State {
+    fn new(nonce: &str, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(State {
+            nonce: nonce.to_string(),
             player_id,
+            snapshot: Value::Null,
         })
     }
 }
 
-impl CommandExt for Chat {
-    fn execute(self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
-        let mut game = game.lock();
-        let username = game.player_username_by_id(self.player_id).unwrap();
-
-        game.add_message(&username, &self.msg);
+impl CommandExt for State {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let snapshot = game.lock().snapshot(self.player_id);
+        self.snapshot = serde_json::to_value(snapshot).unwrap();
 
         self
     }
 
-    fn respond(self: Box<Self>, sender: Arc<Mutex<Sender<UnixStream>>>) -> Box<dyn CommandExt> {
-        util::sync!(sender.lock().send_text(format!("{}", self.nonce))).unwrap();
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.reply(
+            format!("{}\nSUCCESS\n{}", self.nonce, self.snapshot),
+            &json!({ "nonce": self.nonce, "status": "SUCCESS", "state": self.snapshot }),
+        );
 
         self
     }
@@ -350,7 +575,15 @@ impl CommandExt for Chat {
         self.nonce.clone()
     }
 
-    fn is_chat(&self) -> bool {
+    fn allowed_in_lobby(&self) -> bool {
+        true
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn read_only(&self) -> bool {
         true
     }
 
@@ -359,31 +592,42 @@ impl CommandExt for Chat {
     }
 }
 
+/// The catalog of error codes, names and messages, so clients can show
+/// their own translations.
 #[derive(Debug, Default)]
-struct Error {
+struct Errors {
     nonce: String,
-    code: String,
 }
 
-impl Error {
-    fn new(nonce: &String, code: String) -> Box<dyn CommandExt> {
-        Box::new(Error {
-            nonce: nonce.clone(),
-            code,
+impl Errors {
+    fn new(nonce: &str) -> Box<dyn CommandExt> {
+        Box::new(Errors {
+            nonce: nonce.to_string(),
         })
     }
 }
 
-impl CommandExt for Error {
+impl CommandExt for Errors {
     fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
         self
     }
 
-    fn respond(self: Box<Self>, sender: Arc<Mutex<Sender<UnixStream>>>) -> Box<dyn CommandExt> {
-        util::sync!(sender
-            .lock()
-            .send_text(format!("-{}\n{}", self.nonce, self.code)))
-        .unwrap();
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        let errors = GameError::ALL
+            .iter()
+            .map(|error| {
+                json!({
+                    "code": error.code(),
+                    "name": error.name(),
+                    "message": error.message(),
+                })
+            })
+            .collect::<Value>();
+
+        client.reply(
+            format!("{}\nSUCCESS\n{}", self.nonce, errors),
+            &json!({ "nonce": self.nonce, "status": "SUCCESS", "errors": errors }),
+        );
 
         self
     }
@@ -392,12 +636,1695 @@ impl CommandExt for Error {
         self.nonce.clone()
     }
 
-    fn is_error(&self) -> bool {
+    fn allowed_in_lobby(&self) -> bool {
+        true
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Resends the events a client missed after the sequence number it last saw.
+#[derive(Debug, Default)]
+struct Resync {
+    nonce: String,
+    player_id: usize,
+    since: u64,
+    missed: Vec<(u64, Event)>,
+    game: Option<Arc<Mutex<Session>>>,
+}
+
+impl Resync {
+    fn new(nonce: &str, since: u64, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(Resync {
+            nonce: nonce.to_string(),
+            player_id,
+            since,
+            missed: vec![],
+            game: None,
+        })
+    }
+}
+
+impl CommandExt for Resync {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        self.game = Some(game.clone());
+
+        match game.lock().sync(self.player_id, self.since) {
+            Ok(missed) => {
+                self.missed = missed;
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        let game = self.game.as_ref().unwrap();
+        for (seq, event) in &self.missed {
+            back::send(game, client, *seq, event.clone());
+        }
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn allowed_in_lobby(&self) -> bool {
+        true
+    }
+
+    fn allowed_in_debt(&self) -> bool {
         true
     }
 
-    fn error_code(&self) -> Option<String> {
-        Some(self.code.clone())
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Chat {
+    nonce: String,
+    msg: String,
+    player_id: usize,
+    events: Vec<Event>,
+}
+
+impl Chat {
+    pub fn new(nonce: &str, msg: String, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(Chat {
+            nonce: nonce.to_string(),
+            msg: {
+                if msg.is_empty() {
+                    return Error::new(nonce, GameError::ChatEmpty);
+                }
+
+                if msg.len() > 12 {
+                    return Error::new(nonce, GameError::ChatTooLong);
+                }
+
+                msg
+            },
+            player_id,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for Chat {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+        let username = game.player_username_by_id(self.player_id).unwrap();
+
+        if game.is_muted(self.player_id) {
+            return Error::new(&self.nonce, GameError::Muted);
+        }
+
+        game.add_message(&username, &self.msg);
+        self.events = vec![Event::Msg(Message::new(&username, &self.msg))];
+
+        self
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.reply(
+            self.nonce.clone(),
+            &json!({ "nonce": self.nonce, "status": "SUCCESS" }),
+        );
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn allowed_in_lobby(&self) -> bool {
+        true
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Ready {
+    nonce: String,
+    player_id: usize,
+    events: Vec<Event>,
+}
+
+impl Ready {
+    fn new(nonce: &str, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(Ready {
+            nonce: nonce.to_string(),
+            player_id,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for Ready {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.toggle_ready(self.player_id) {
+            Ok(ready) => {
+                let username = game.player_username_by_id(self.player_id).unwrap();
+                self.events = vec![Event::Ready(back::Ready::new(&username, ready))];
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn allowed_in_lobby(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Start {
+    nonce: String,
+    player_id: usize,
+    events: Vec<Event>,
+}
+
+impl Start {
+    fn new(nonce: &str, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(Start {
+            nonce: nonce.to_string(),
+            player_id,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for Start {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.start(self.player_id) {
+            Ok(order) => {
+                let usernames = order
+                    .iter()
+                    .map(|id| game.player_username_by_id(*id).unwrap())
+                    .collect::<Vec<_>>();
+
+                self.events = vec![
+                    Event::Start(back::Start::new(
+                        game.rules().starting_cash,
+                        usernames.clone(),
+                    )),
+                    Event::Turn(back::Turn::new(&usernames[0])),
+                ];
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn allowed_in_lobby(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Kick {
+    nonce: String,
+    player_id: usize,
+    target: String,
+    /// Socket of the kicked player, closed once the host has their response.
+    sock: Option<Arc<Mutex<Sender<UnixStream>>>>,
+    events: Vec<Event>,
+    auction: Option<usize>,
+}
+
+impl Kick {
+    fn new(nonce: &str, target: String, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(Kick {
+            nonce: nonce.to_string(),
+            player_id,
+            target,
+            sock: None,
+            events: vec![],
+            auction: None,
+        })
+    }
+}
+
+impl CommandExt for Kick {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut session = game.lock();
+
+        match session.kick(self.player_id, &self.target) {
+            Ok((target, outcomes, auction)) => {
+                self.sock = session.players()[target].sock();
+                session.disconnect(target);
+
+                self.events = vec![Event::System(back::System::new(
+                    "KICK",
+                    vec![self.target.clone()],
+                ))];
+                self.events.extend(
+                    outcomes
+                        .iter()
+                        .map(|outcome| Event::from_outcome(outcome, &session)),
+                );
+
+                if auction.is_some() {
+                    self.events.push(Event::Auction(back::Auction::new(
+                        session.auction_position().unwrap(),
+                        session.auction_timeout().as_secs(),
+                    )));
+                }
+
+                self.auction = auction;
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        if let Some(sock) = &self.sock {
+            // The connection may already be gone on the other end.
+            util::sync!(sock.lock().close()).ok();
+        }
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn auction(&self) -> Option<usize> {
+        self.auction
+    }
+
+    fn allowed_in_lobby(&self) -> bool {
+        true
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Mute {
+    nonce: String,
+    player_id: usize,
+    target: String,
+    events: Vec<Event>,
+}
+
+impl Mute {
+    fn new(nonce: &str, target: String, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(Mute {
+            nonce: nonce.to_string(),
+            player_id,
+            target,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for Mute {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.mute(self.player_id, &self.target) {
+            Ok(muted) => {
+                self.events = vec![Event::System(back::System::new(
+                    "MUTE",
+                    vec![self.target.clone(), muted.to_string()],
+                ))];
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn allowed_in_lobby(&self) -> bool {
+        true
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct TransferHost {
+    nonce: String,
+    player_id: usize,
+    target: String,
+    events: Vec<Event>,
+}
+
+impl TransferHost {
+    fn new(nonce: &str, target: String, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(TransferHost {
+            nonce: nonce.to_string(),
+            player_id,
+            target,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for TransferHost {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.transfer_host(self.player_id, &self.target) {
+            Ok(()) => {
+                self.events = vec![Event::System(back::System::new(
+                    "HOST",
+                    vec![self.target.clone()],
+                ))];
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn allowed_in_lobby(&self) -> bool {
+        true
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Roll {
+    nonce: String,
+    player_id: usize,
+    events: Vec<Event>,
+}
+
+impl Roll {
+    fn new(nonce: &str, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(Roll {
+            nonce: nonce.to_string(),
+            player_id,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for Roll {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.roll(self.player_id) {
+            Ok(result) => {
+                let username = game.player_username_by_id(self.player_id).unwrap();
+
+                self.events.push(Event::Roll(back::Roll::new(
+                    &username,
+                    result.dice,
+                    result.position,
+                )));
+                self.events.extend(
+                    result
+                        .outcomes
+                        .iter()
+                        .map(|outcome| Event::from_outcome(outcome, &game)),
+                );
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct EndTurn {
+    nonce: String,
+    player_id: usize,
+    events: Vec<Event>,
+}
+
+impl EndTurn {
+    fn new(nonce: &str, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(EndTurn {
+            nonce: nonce.to_string(),
+            player_id,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for EndTurn {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.end_turn(self.player_id) {
+            Ok(outcome) => {
+                self.events = vec![Event::from_outcome(&outcome, &game)];
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Buy {
+    nonce: String,
+    player_id: usize,
+    events: Vec<Event>,
+}
+
+impl Buy {
+    fn new(nonce: &str, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(Buy {
+            nonce: nonce.to_string(),
+            player_id,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for Buy {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.buy(self.player_id) {
+            Ok((position, price)) => {
+                let username = game.player_username_by_id(self.player_id).unwrap();
+                self.events.push(Event::Purchase(back::Purchase::new(
+                    &username, position, price,
+                )));
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Decline {
+    nonce: String,
+    player_id: usize,
+    events: Vec<Event>,
+    auction: Option<usize>,
+}
+
+impl Decline {
+    fn new(nonce: &str, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(Decline {
+            nonce: nonce.to_string(),
+            player_id,
+            events: vec![],
+            auction: None,
+        })
+    }
+}
+
+impl CommandExt for Decline {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut session = game.lock();
+
+        match session.decline(self.player_id) {
+            Ok((position, auction)) => {
+                let username = session.player_username_by_id(self.player_id).unwrap();
+                self.events
+                    .push(Event::Decline(back::Decline::new(&username, position)));
+
+                if auction.is_some() {
+                    self.events.push(Event::Auction(back::Auction::new(
+                        position,
+                        session.auction_timeout().as_secs(),
+                    )));
+                }
+
+                self.auction = auction;
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn auction(&self) -> Option<usize> {
+        self.auction
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Bid {
+    nonce: String,
+    player_id: usize,
+    amount: u32,
+    username: String,
+}
+
+impl Bid {
+    fn new(nonce: &str, amount: u32, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(Bid {
+            nonce: nonce.to_string(),
+            player_id,
+            amount,
+            username: String::new(),
+        })
+    }
+}
+
+impl CommandExt for Bid {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.bid(self.player_id, self.amount) {
+            Ok(()) => {
+                self.username = game.player_username_by_id(self.player_id).unwrap();
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        vec![Event::Bid(back::Bid::new(&self.username, self.amount))]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct TradePropose {
+    nonce: String,
+    player_id: usize,
+    target: String,
+    offer: Bundle,
+    request: Bundle,
+    trade: Option<Trade>,
+    notifications: Vec<(usize, Event)>,
+}
+
+impl TradePropose {
+    fn new(
+        nonce: &str,
+        target: String,
+        offer: Bundle,
+        request: Bundle,
+        player_id: usize,
+    ) -> Box<dyn CommandExt> {
+        Box::new(TradePropose {
+            nonce: nonce.to_string(),
+            player_id,
+            target,
+            offer: offer.normalized(),
+            request: request.normalized(),
+            trade: None,
+            notifications: vec![],
+        })
+    }
+}
+
+impl CommandExt for TradePropose {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.propose_trade(
+            self.player_id,
+            &self.target,
+            self.offer.clone(),
+            self.request.clone(),
+        ) {
+            Ok(trade) => {
+                let offer = Event::TradeOffer(back::TradeOffer::new(&trade, &game));
+
+                self.notifications = vec![(trade.proposer, offer.clone()), (trade.target, offer)];
+                self.trade = Some(trade);
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        let id = self.trade.as_ref().unwrap().id;
+        client.reply(
+            format!("{}\n{}", self.nonce, id),
+            &json!({ "nonce": self.nonce, "status": "SUCCESS", "trade": id }),
+        );
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn notifications(&self) -> Vec<(usize, Event)> {
+        self.notifications.clone()
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct TradeAccept {
+    nonce: String,
+    player_id: usize,
+    id: usize,
+    events: Vec<Event>,
+    notifications: Vec<(usize, Event)>,
+}
+
+impl TradeAccept {
+    fn new(nonce: &str, id: usize, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(TradeAccept {
+            nonce: nonce.to_string(),
+            player_id,
+            id,
+            events: vec![],
+            notifications: vec![],
+        })
+    }
+}
+
+impl CommandExt for TradeAccept {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.accept_trade(self.player_id, self.id) {
+            Ok(trade) => {
+                self.events
+                    .push(Event::TradeDone(back::TradeDone::new(&trade, &game)));
+                self.notifications = mortgage_decisions(&game, &[trade.proposer, trade.target]);
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn notifications(&self) -> Vec<(usize, Event)> {
+        self.notifications.clone()
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct TradeReject {
+    nonce: String,
+    player_id: usize,
+    id: usize,
+    notifications: Vec<(usize, Event)>,
+}
+
+impl TradeReject {
+    fn new(nonce: &str, id: usize, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(TradeReject {
+            nonce: nonce.to_string(),
+            player_id,
+            id,
+            notifications: vec![],
+        })
+    }
+}
+
+impl CommandExt for TradeReject {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.reject_trade(self.player_id, self.id) {
+            Ok(trade) => {
+                let username = game.player_username_by_id(self.player_id).unwrap();
+                let event = Event::TradeRejected(back::TradeRejected::new(trade.id, &username));
+
+                self.notifications = vec![(trade.proposer, event.clone()), (trade.target, event)];
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn notifications(&self) -> Vec<(usize, Event)> {
+        self.notifications.clone()
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct TradeCounter {
+    nonce: String,
+    player_id: usize,
+    id: usize,
+    offer: Bundle,
+    request: Bundle,
+    counter: Option<Trade>,
+    notifications: Vec<(usize, Event)>,
+}
+
+impl TradeCounter {
+    fn new(
+        nonce: &str,
+        id: usize,
+        offer: Bundle,
+        request: Bundle,
+        player_id: usize,
+    ) -> Box<dyn CommandExt> {
+        Box::new(TradeCounter {
+            nonce: nonce.to_string(),
+            player_id,
+            id,
+            offer: offer.normalized(),
+            request: request.normalized(),
+            counter: None,
+            notifications: vec![],
+        })
+    }
+}
+
+impl CommandExt for TradeCounter {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.counter_trade(
+            self.player_id,
+            self.id,
+            self.offer.clone(),
+            self.request.clone(),
+        ) {
+            Ok((original, counter)) => {
+                let username = game.player_username_by_id(self.player_id).unwrap();
+                let rejected =
+                    Event::TradeRejected(back::TradeRejected::new(original.id, &username));
+                let offer = Event::TradeOffer(back::TradeOffer::new(&counter, &game));
+
+                self.notifications = vec![
+                    (counter.proposer, rejected.clone()),
+                    (counter.target, rejected),
+                    (counter.proposer, offer.clone()),
+                    (counter.target, offer),
+                ];
+                self.counter = Some(counter);
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        let id = self.counter.as_ref().unwrap().id;
+        client.reply(
+            format!("{}\n{}", self.nonce, id),
+            &json!({ "nonce": self.nonce, "status": "SUCCESS", "trade": id }),
+        );
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn notifications(&self) -> Vec<(usize, Event)> {
+        self.notifications.clone()
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Build {
+    nonce: String,
+    player_id: usize,
+    position: usize,
+    events: Vec<Event>,
+}
+
+impl Build {
+    fn new(nonce: &str, position: usize, player_id: usize) -> Box<dyn CommandExt> {
+        if position >= BOARD_SIZE {
+            return Error::new(nonce, GameError::InvalidPosition);
+        }
+
+        Box::new(Build {
+            nonce: nonce.to_string(),
+            player_id,
+            position,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for Build {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.build(self.player_id, self.position) {
+            Ok(buildings) => {
+                let username = game.player_username_by_id(self.player_id).unwrap();
+                self.events.push(Event::Build(back::Build::new(
+                    &username,
+                    self.position,
+                    buildings,
+                )));
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct SellBuilding {
+    nonce: String,
+    player_id: usize,
+    position: usize,
+    events: Vec<Event>,
+}
+
+impl SellBuilding {
+    fn new(nonce: &str, position: usize, player_id: usize) -> Box<dyn CommandExt> {
+        if position >= BOARD_SIZE {
+            return Error::new(nonce, GameError::InvalidPosition);
+        }
+
+        Box::new(SellBuilding {
+            nonce: nonce.to_string(),
+            player_id,
+            position,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for SellBuilding {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.sell_building(self.player_id, self.position) {
+            Ok((buildings, _)) => {
+                let username = game.player_username_by_id(self.player_id).unwrap();
+                self.events
+                    .push(Event::SellBuilding(back::SellBuilding::new(
+                        &username,
+                        self.position,
+                        buildings,
+                    )));
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Mortgage {
+    nonce: String,
+    player_id: usize,
+    position: usize,
+    events: Vec<Event>,
+}
+
+impl Mortgage {
+    fn new(nonce: &str, position: usize, player_id: usize) -> Box<dyn CommandExt> {
+        if position >= BOARD_SIZE {
+            return Error::new(nonce, GameError::InvalidPosition);
+        }
+
+        Box::new(Mortgage {
+            nonce: nonce.to_string(),
+            player_id,
+            position,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for Mortgage {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.mortgage(self.player_id, self.position) {
+            Ok(_) => {
+                let username = game.player_username_by_id(self.player_id).unwrap();
+                self.events.push(Event::Mortgage(back::Mortgage::new(
+                    &username,
+                    self.position,
+                )));
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Unmortgage {
+    nonce: String,
+    player_id: usize,
+    position: usize,
+    events: Vec<Event>,
+}
+
+impl Unmortgage {
+    fn new(nonce: &str, position: usize, player_id: usize) -> Box<dyn CommandExt> {
+        if position >= BOARD_SIZE {
+            return Error::new(nonce, GameError::InvalidPosition);
+        }
+
+        Box::new(Unmortgage {
+            nonce: nonce.to_string(),
+            player_id,
+            position,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for Unmortgage {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.unmortgage(self.player_id, self.position) {
+            Ok(_) => {
+                let username = game.player_username_by_id(self.player_id).unwrap();
+                self.events.push(Event::Unmortgage(back::Unmortgage::new(
+                    &username,
+                    self.position,
+                )));
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct KeepMortgage {
+    nonce: String,
+    player_id: usize,
+    position: usize,
+    events: Vec<Event>,
+}
+
+impl KeepMortgage {
+    fn new(nonce: &str, position: usize, player_id: usize) -> Box<dyn CommandExt> {
+        if position >= BOARD_SIZE {
+            return Error::new(nonce, GameError::InvalidPosition);
+        }
+
+        Box::new(KeepMortgage {
+            nonce: nonce.to_string(),
+            player_id,
+            position,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for KeepMortgage {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.keep_mortgage(self.player_id, self.position) {
+            Ok(_) => {
+                let username = game.player_username_by_id(self.player_id).unwrap();
+                self.events
+                    .push(Event::KeepMortgage(back::KeepMortgage::new(
+                        &username,
+                        self.position,
+                    )));
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct PayBail {
+    nonce: String,
+    player_id: usize,
+    events: Vec<Event>,
+}
+
+impl PayBail {
+    fn new(nonce: &str, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(PayBail {
+            nonce: nonce.to_string(),
+            player_id,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for PayBail {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.pay_bail(self.player_id) {
+            Ok(outcomes) => {
+                self.events = outcomes
+                    .iter()
+                    .map(|outcome| Event::from_outcome(outcome, &game))
+                    .collect();
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct UseJailCard {
+    nonce: String,
+    player_id: usize,
+    events: Vec<Event>,
+}
+
+impl UseJailCard {
+    fn new(nonce: &str, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(UseJailCard {
+            nonce: nonce.to_string(),
+            player_id,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for UseJailCard {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.use_jail_card(self.player_id) {
+            Ok(outcomes) => {
+                self.events = outcomes
+                    .iter()
+                    .map(|outcome| Event::from_outcome(outcome, &game))
+                    .collect();
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct PayDebt {
+    nonce: String,
+    player_id: usize,
+    events: Vec<Event>,
+}
+
+impl PayDebt {
+    fn new(nonce: &str, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(PayDebt {
+            nonce: nonce.to_string(),
+            player_id,
+            events: vec![],
+        })
+    }
+}
+
+impl CommandExt for PayDebt {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut game = game.lock();
+
+        match game.pay_debts(self.player_id) {
+            Ok(outcomes) => {
+                self.events = outcomes
+                    .iter()
+                    .map(|outcome| Event::from_outcome(outcome, &game))
+                    .collect();
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Bankrupt {
+    nonce: String,
+    player_id: usize,
+    events: Vec<Event>,
+    notifications: Vec<(usize, Event)>,
+    auction: Option<usize>,
+}
+
+impl Bankrupt {
+    fn new(nonce: &str, player_id: usize) -> Box<dyn CommandExt> {
+        Box::new(Bankrupt {
+            nonce: nonce.to_string(),
+            player_id,
+            events: vec![],
+            notifications: vec![],
+            auction: None,
+        })
+    }
+}
+
+impl CommandExt for Bankrupt {
+    fn execute(mut self: Box<Self>, game: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        let mut session = game.lock();
+
+        match session.declare_bankruptcy(self.player_id) {
+            Ok((outcomes, auction)) => {
+                self.events = outcomes
+                    .iter()
+                    .map(|outcome| Event::from_outcome(outcome, &session))
+                    .collect();
+
+                // A creditor taking over mortgaged deeds has to decide on them.
+                let creditor = outcomes.iter().find_map(|outcome| match outcome {
+                    Outcome::Bankrupt { creditor, .. } => *creditor,
+                    _ => None,
+                });
+                self.notifications = mortgage_decisions(&session, creditor.as_slice());
+
+                if auction.is_some() {
+                    self.events.push(Event::Auction(back::Auction::new(
+                        session.auction_position().unwrap(),
+                        session.auction_timeout().as_secs(),
+                    )));
+                }
+
+                self.auction = auction;
+
+                self
+            }
+            Err(err) => Error::new(&self.nonce, err),
+        }
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.success(&self.nonce);
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn auction(&self) -> Option<usize> {
+        self.auction
+    }
+
+    fn notifications(&self) -> Vec<(usize, Event)> {
+        self.notifications.clone()
+    }
+
+    fn allowed_in_debt(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Error {
+    nonce: String,
+    error: GameError,
+}
+
+impl Error {
+    fn new(nonce: &str, error: GameError) -> Box<dyn CommandExt> {
+        Box::new(Error {
+            nonce: nonce.to_string(),
+            error,
+        })
+    }
+}
+
+impl CommandExt for Error {
+    fn execute(self: Box<Self>, _: Arc<Mutex<Session>>) -> Box<dyn CommandExt> {
+        self
+    }
+
+    fn respond(self: Box<Self>, client: &Client) -> Box<dyn CommandExt> {
+        client.reply(
+            format!(
+                "-{}\n{}\n{}\n{}",
+                self.nonce,
+                self.error.code(),
+                self.error.name(),
+                self.error.message()
+            ),
+            &json!({
+                "nonce": self.nonce,
+                "status": "ERROR",
+                "error": self.error.code(),
+                "name": self.error.name(),
+                "message": self.error.message(),
+            }),
+        );
+
+        self
+    }
+
+    fn nonce(&self) -> String {
+        self.nonce.clone()
+    }
+
+    fn is_error(&self) -> bool {
+        true
+    }
+
+    fn error(&self) -> Option<GameError> {
+        Some(self.error)
+    }
+
+    fn read_only(&self) -> bool {
+        true
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -0,0 +1,115 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use async_std::io::{ReadExt, WriteExt};
+use async_std::os::unix::net::UnixStream;
+use eyre::{bail, Result};
+use log::error;
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::game::lobby::Phase;
+use crate::game::Session;
+use crate::util;
+
+/// The HTTP server's internal API.
+const HOST_SOCK: &str = "/monopoly_socks/host";
+
+/// What a game tells the HTTP server about itself, posted as JSON with its
+/// kind under `notice`.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+#[serde(tag = "notice", rename_all = "snake_case")]
+enum Notice {
+    Status { players: usize, phase: Phase },
+    Joined { username: String },
+    Ended { winner: String },
+}
+
+/// The status last sent, so unchanged ones are not sent again.
+static REPORTED: Mutex<Option<Notice>> = Mutex::new(None);
+
+/// The game code, which is the name of the socket the game listens on.
+pub fn game_code() -> String {
+    let path = std::env::var("MONOPOLY_GAME_PATH").unwrap();
+
+    Path::new(&path)
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Sends an HTTP request to the internal API, returning the raw response if
+/// it was successful.
+fn request(method: &str, path: &str, body: &str) -> Result<String> {
+    let mut stream = util::sync!(UnixStream::connect(HOST_SOCK))?;
+
+    let request = format!(
+        "{method} {path} HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len(),
+    );
+    util::sync!(stream.write_all(request.as_bytes()))?;
+
+    let mut response = String::new();
+    util::sync!(stream.read_to_string(&mut response))?;
+
+    if !response.starts_with("HTTP/1.1 2") {
+        bail!("{}", response.lines().next().unwrap_or_default());
+    }
+
+    Ok(response)
+}
+
+fn send(notice: &Notice) -> Result<()> {
+    let body = serde_json::to_string(notice).unwrap();
+    request(
+        "POST",
+        &format!("/api/internal/games/{}", game_code()),
+        &body,
+    )?;
+
+    Ok(())
+}
+
+/// Tells the HTTP server how many players the game has and what phase it is
+/// in, if that changed since the last report, and who won once it ends.
+pub fn report(game: &Arc<Mutex<Session>>) {
+    let (status, winner) = {
+        let game = game.lock();
+        let status = Notice::Status {
+            players: game.player_count(),
+            phase: game.phase(),
+        };
+
+        let winner = game.winner().and_then(|id| game.player_username_by_id(id));
+        (status, winner)
+    };
+
+    let mut reported = REPORTED.lock();
+    if reported.as_ref() == Some(&status) {
+        return;
+    }
+
+    if let Err(err) = send(&status) {
+        error!("Failed to report game status: {}", err);
+        return;
+    }
+    *reported = Some(status);
+
+    if let Some(winner) = winner {
+        if let Err(err) = send(&Notice::Ended { winner }) {
+            error!("Failed to report game end: {}", err);
+        }
+    }
+}
+
+/// Tells the HTTP server `username` took a seat.
+pub fn joined(username: &str) {
+    let notice = Notice::Joined {
+        username: username.to_string(),
+    };
+
+    if let Err(err) = send(&notice) {
+        error!("Failed to report {} joining: {}", username, err);
+    }
+}
@@ -0,0 +1,393 @@
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use eyre::Result;
+use log::{error, info, warn};
+use parking_lot::{Mutex, MutexGuard};
+use serde::{Deserialize, Serialize};
+
+use crate::api::back;
+use crate::api::front::{self, CommandExt};
+use crate::api::internal;
+use crate::api::wire::Request;
+use crate::game::Session;
+
+/// A line of the command log, naming its kind under `entry`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "entry", rename_all = "snake_case")]
+enum Entry {
+    /// Starts the log kept since the snapshot of the same generation.
+    Snapshot { generation: u64 },
+    /// A command that succeeded, with the seed its dice and shuffles came from.
+    Command {
+        player: usize,
+        seed: u64,
+        request: Request,
+    },
+    /// The auction timer ran out.
+    AuctionClosed,
+}
+
+/// The game as saved, numbered so a log left over from before it is not
+/// replayed on top of it.
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot<G> {
+    generation: u64,
+    game: G,
+}
+
+#[derive(Debug)]
+struct Log {
+    file: File,
+    snapshot: PathBuf,
+    generation: u64,
+}
+
+impl Log {
+    fn append(&mut self, entry: &Entry) {
+        let mut line = serde_json::to_vec(entry).unwrap();
+        line.push(b'\n');
+
+        if let Err(err) = self.file.write_all(&line) {
+            error!("Failed to log {:?}: {}", entry, err);
+        }
+    }
+}
+
+/// The open command log, or `None` while persistence is off. It is held
+/// while the game changes so a snapshot never lands between a change and
+/// its entry.
+static LOG: Mutex<Option<Log>> = Mutex::new(None);
+
+/// Snapshot and log paths under `MONOPOLY_DATA_DIR`, if it is set.
+fn paths() -> Option<(PathBuf, PathBuf)> {
+    let dir = PathBuf::from(std::env::var("MONOPOLY_DATA_DIR").ok()?);
+    let code = internal::game_code();
+
+    Some((
+        dir.join(format!("{code}.snapshot.json")),
+        dir.join(format!("{code}.log")),
+    ))
+}
+
+/// Executes `command` from `player_id`, logging it if it succeeds.
+pub fn record(
+    game: &Arc<Mutex<Session>>,
+    player_id: usize,
+    request: Request,
+    command: Box<dyn CommandExt>,
+) -> Box<dyn CommandExt> {
+    let mut log = LOG.lock();
+
+    let seed = rand::random();
+    game.lock().reseed(seed);
+
+    let command = command.execute(game.clone());
+
+    if let Some(log) = log.as_mut().filter(|_| !command.is_error()) {
+        log.append(&Entry::Command {
+            player: player_id,
+            seed,
+            request,
+        });
+    }
+
+    command
+}
+
+/// Keeps commands and snapshots out while an auction timer decides whether
+/// to close its auction.
+pub struct Closing(MutexGuard<'static, Option<Log>>);
+
+impl Closing {
+    /// Logs that the auction was closed.
+    pub fn closed(mut self) {
+        if let Some(log) = self.0.as_mut() {
+            log.append(&Entry::AuctionClosed);
+        }
+    }
+}
+
+pub fn closing_auction() -> Closing {
+    Closing(LOG.lock())
+}
+
+/// Saves the game and starts a new log from it.
+pub fn snapshot(game: &Arc<Mutex<Session>>) -> Result<()> {
+    let mut log = LOG.lock();
+    let Some(log) = log.as_mut() else {
+        return Ok(());
+    };
+
+    let generation = log.generation + 1;
+    let snapshot = serde_json::to_vec(&Snapshot {
+        generation,
+        game: &*game.lock(),
+    })?;
+
+    // Written aside first so a crash midway leaves the last snapshot whole.
+    let partial = log.snapshot.with_extension("partial");
+    std::fs::write(&partial, snapshot)?;
+    std::fs::rename(&partial, &log.snapshot)?;
+
+    log.file.set_len(0)?;
+    log.generation = generation;
+    log.append(&Entry::Snapshot { generation });
+
+    Ok(())
+}
+
+/// Snapshots the game every `interval`, forever.
+pub async fn keep_snapshots(game: &Arc<Mutex<Session>>, interval: Duration) -> Result<()> {
+    loop {
+        async_std::task::sleep(interval).await;
+
+        if let Err(err) = snapshot(game) {
+            error!("Failed to snapshot game: {}", err);
+        }
+    }
+}
+
+/// Applies the entries of `log` that follow the snapshot of `generation`,
+/// returning how many there were.
+fn replay(game: &Arc<Mutex<Session>>, generation: u64, log: &str) -> usize {
+    let mut current = false;
+    let mut replayed = 0;
+
+    for line in log.lines() {
+        let Ok(entry) = serde_json::from_str::<Entry>(line) else {
+            // Most likely the last line, cut short by the crash.
+            warn!("Stopped replaying at unreadable log entry: {}", line);
+            break;
+        };
+
+        match entry {
+            Entry::Snapshot { generation: logged } => {
+                current = logged == generation;
+                continue;
+            }
+            _ if !current => continue,
+            Entry::Command {
+                player,
+                seed,
+                request,
+            } => {
+                game.lock().reseed(seed);
+                front::replay(game, player, &request);
+            }
+            Entry::AuctionClosed => {
+                let (events, _) = back::close_auction(&mut game.lock());
+
+                for event in &events {
+                    back::broadcast(game, event);
+                }
+            }
+        }
+
+        replayed += 1;
+    }
+
+    replayed
+}
+
+/// Restores the game from its snapshot and log in `MONOPOLY_DATA_DIR`, if
+/// any, and starts logging to it. Players take their seats back with
+/// `RESUME`.
+pub fn restore(game: &Arc<Mutex<Session>>) -> Result<()> {
+    let Some((snapshot_path, log_path)) = paths() else {
+        return Ok(());
+    };
+
+    let mut generation = 0;
+    match std::fs::read(&snapshot_path) {
+        Ok(data) => {
+            let snapshot = serde_json::from_slice::<Snapshot<Session>>(&data)?;
+            generation = snapshot.generation;
+            *game.lock() = snapshot.game;
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => (),
+        Err(err) => return Err(err.into()),
+    }
+
+    let replayed = match std::fs::read_to_string(&log_path) {
+        Ok(log) => replay(game, generation, &log),
+        Err(err) if err.kind() == ErrorKind::NotFound => 0,
+        Err(err) => return Err(err.into()),
+    };
+
+    if generation > 0 {
+        info!(
+            "Restored game from snapshot {} and {} logged changes",
+            generation, replayed
+        );
+    }
+
+    let auction = {
+        let mut session = game.lock();
+        session.restored();
+        session.auction_id()
+    };
+
+    if let Some(id) = auction {
+        let game = game.clone();
+        std::thread::spawn(move || back::watch_auction(&game, id));
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)?;
+
+    *LOG.lock() = Some(Log {
+        file,
+        snapshot: snapshot_path,
+        generation,
+    });
+
+    snapshot(game)
+}
+
+/// Deletes the saved game once it has shut down for good.
+pub fn remove() -> Result<()> {
+    let Some((snapshot_path, log_path)) = paths() else {
+        return Ok(());
+    };
+
+    *LOG.lock() = None;
+
+    for path in [snapshot_path, log_path] {
+        match std::fs::remove_file(&path) {
+            Ok(()) => (),
+            Err(err) if err.kind() == ErrorKind::NotFound => (),
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::wire::Action;
+    use crate::game::error::GameError;
+    use crate::game::lobby::Phase;
+
+    fn command(player: usize, seed: u64, action: Action) -> Entry {
+        Entry::Command {
+            player,
+            seed,
+            request: Request {
+                nonce: seed.to_string(),
+                action,
+            },
+        }
+    }
+
+    fn init(username: &str, host_key: Option<&str>) -> Action {
+        Action::Init {
+            username: username.to_string(),
+            host_key: host_key.map(str::to_string),
+        }
+    }
+
+    fn log(entries: &[Entry]) -> String {
+        entries
+            .iter()
+            .map(|entry| serde_json::to_string(entry).unwrap() + "\n")
+            .collect()
+    }
+
+    #[test]
+    fn replaying_a_log_rebuilds_the_game() {
+        std::env::set_var("MONOPOLY_HOST_KEY", "host");
+
+        let log = log(&[
+            Entry::Snapshot { generation: 1 },
+            command(0, 1, init("alice", Some("host"))),
+            command(1, 2, init("bob", None)),
+            command(0, 3, Action::Ready),
+            command(1, 4, Action::Ready),
+            command(0, 5, Action::Start),
+            command(0, 6, Action::Roll),
+            command(1, 7, Action::Roll),
+        ]);
+
+        let first = Arc::new(Mutex::new(Session::new()));
+        let second = Arc::new(Mutex::new(Session::new()));
+        assert_eq!(replay(&first, 1, &log), 7);
+        assert_eq!(replay(&second, 1, &log), 7);
+
+        let mut first = first.lock();
+        let mut second = second.lock();
+        assert_eq!(first.phase(), Phase::InProgress);
+        assert_eq!(
+            serde_json::to_value(first.players()).unwrap(),
+            serde_json::to_value(second.players()).unwrap(),
+        );
+    }
+
+    #[test]
+    fn only_the_current_generation_is_replayed() {
+        std::env::set_var("MONOPOLY_HOST_KEY", "host");
+
+        let mut log = log(&[
+            Entry::Snapshot { generation: 1 },
+            command(0, 1, init("alice", None)),
+            Entry::Snapshot { generation: 2 },
+            command(0, 2, init("bob", None)),
+        ]);
+        log.push_str("{\"entry\":\"comm\n");
+        log.push_str(&serde_json::to_string(&command(1, 3, init("carol", None))).unwrap());
+
+        let game = Arc::new(Mutex::new(Session::new()));
+        assert_eq!(replay(&game, 2, &log), 1);
+
+        let game = game.lock();
+        assert_eq!(game.player_count(), 1);
+        assert_eq!(game.player_id_by_username("bob"), Some(0));
+    }
+
+    #[test]
+    fn resume_after_restoring_a_logged_init() {
+        let dir = std::env::temp_dir().join(format!("monopoly-persist-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::env::set_var("MONOPOLY_DATA_DIR", &dir);
+        std::env::set_var("MONOPOLY_GAME_PATH", dir.join("PERSISTS"));
+        std::env::set_var("MONOPOLY_HOST_KEY", "host");
+
+        let snapshot = Snapshot {
+            generation: 1,
+            game: &Session::new(),
+        };
+        std::fs::write(
+            dir.join("PERSISTS.snapshot.json"),
+            serde_json::to_vec(&snapshot).unwrap(),
+        )
+        .unwrap();
+
+        let log = log(&[
+            Entry::Snapshot { generation: 1 },
+            command(0, 7, init("alice", None)),
+        ]);
+        std::fs::write(dir.join("PERSISTS.log"), log).unwrap();
+
+        let game = Arc::new(Mutex::new(Session::new()));
+        restore(&game).unwrap();
+
+        let mut session = game.lock();
+        assert!(session.idle_for().is_some());
+
+        let token = session.resume_token(0).to_string();
+        assert_eq!(session.resume(&token), Ok(0));
+        assert_eq!(session.resume(&token), Err(GameError::SeatConnected));
+
+        drop(session);
+        remove().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
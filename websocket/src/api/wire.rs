@@ -0,0 +1,518 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_std::os::unix::net::UnixStream;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use soketto::Sender;
+
+use crate::game::error::GameError;
+use crate::game::trade::Bundle;
+use crate::util;
+
+/// Framing picked through the websocket subprotocol in the handshake.
+/// Clients that ask for none get the newline-delimited text protocol.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Protocol {
+    Text,
+    Json,
+    /// The JSON messages encoded as `MessagePack` in binary frames.
+    MsgPack,
+}
+
+/// A command as sent by the client, whatever the framing. JSON and
+/// `MessagePack` clients send the command's fields alongside the nonce, e.g.
+/// `{"nonce": "7", "command": "BID", "amount": 120}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    pub nonce: String,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+/// A command and its arguments, named under `command`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Action {
+    Init {
+        username: String,
+        host_key: Option<String>,
+    },
+    Resume {
+        token: String,
+    },
+    State,
+    Errors,
+    Sync {
+        since: u64,
+    },
+    Echo {
+        msg: String,
+    },
+    Chat {
+        msg: String,
+    },
+    Roll,
+    EndTurn,
+    Buy,
+    Decline,
+    Bid {
+        amount: u32,
+    },
+    TradePropose {
+        target: String,
+        offer: Bundle,
+        request: Bundle,
+    },
+    TradeAccept {
+        id: usize,
+    },
+    TradeReject {
+        id: usize,
+    },
+    TradeCounter {
+        id: usize,
+        offer: Bundle,
+        request: Bundle,
+    },
+    Build {
+        position: usize,
+    },
+    SellBuilding {
+        position: usize,
+    },
+    Mortgage {
+        position: usize,
+    },
+    Unmortgage {
+        position: usize,
+    },
+    KeepMortgage {
+        position: usize,
+    },
+    PayBail,
+    UseJailCard,
+    Ready,
+    Start,
+    Kick {
+        target: String,
+    },
+    Mute {
+        target: String,
+    },
+    TransferHost {
+        target: String,
+    },
+    PayDebt,
+    Bankrupt,
+}
+
+/// A frame that could not be read as a request, answered with `error`
+/// under its nonce if it had one.
+#[derive(Debug, PartialEq)]
+pub struct Rejected {
+    pub nonce: String,
+    pub error: GameError,
+}
+
+impl Action {
+    /// Reads the arguments of `command` from the lines of a text frame.
+    fn from_text<'a>(
+        command: &str,
+        mut args: impl Iterator<Item = &'a str>,
+    ) -> Result<Action, GameError> {
+        let args = &mut args;
+
+        let action = match command {
+            "INIT" => Action::Init {
+                username: parse(args, GameError::MissingUsername)?,
+                host_key: args.next().map(str::to_string),
+            },
+            "RESUME" => Action::Resume {
+                token: parse(args, GameError::UnknownToken)?,
+            },
+            "STATE" => Action::State,
+            "ERRORS" => Action::Errors,
+            "SYNC" => Action::Sync {
+                since: parse(args, GameError::Malformed)?,
+            },
+            "ECHO" => Action::Echo {
+                msg: parse(args, GameError::MissingEcho)?,
+            },
+            "CHAT" => Action::Chat {
+                msg: args.collect::<Vec<_>>().join("\n"),
+            },
+            "ROLL" => Action::Roll,
+            "END_TURN" => Action::EndTurn,
+            "BUY" => Action::Buy,
+            "DECLINE" => Action::Decline,
+            "BID" => Action::Bid {
+                amount: parse(args, GameError::InvalidBid)?,
+            },
+            "TRADE_PROPOSE" => Action::TradePropose {
+                target: parse(args, GameError::UnknownTradePartner)?,
+                offer: parse_bundle(args).ok_or(GameError::InvalidTrade)?,
+                request: parse_bundle(args).ok_or(GameError::InvalidTrade)?,
+            },
+            "TRADE_ACCEPT" => Action::TradeAccept {
+                id: parse(args, GameError::UnknownTrade)?,
+            },
+            "TRADE_REJECT" => Action::TradeReject {
+                id: parse(args, GameError::UnknownTrade)?,
+            },
+            "TRADE_COUNTER" => Action::TradeCounter {
+                id: parse(args, GameError::UnknownTrade)?,
+                offer: parse_bundle(args).ok_or(GameError::InvalidTrade)?,
+                request: parse_bundle(args).ok_or(GameError::InvalidTrade)?,
+            },
+            "BUILD" => Action::Build {
+                position: parse(args, GameError::InvalidPosition)?,
+            },
+            "SELL_BUILDING" => Action::SellBuilding {
+                position: parse(args, GameError::InvalidPosition)?,
+            },
+            "MORTGAGE" => Action::Mortgage {
+                position: parse(args, GameError::InvalidPosition)?,
+            },
+            "UNMORTGAGE" => Action::Unmortgage {
+                position: parse(args, GameError::InvalidPosition)?,
+            },
+            "KEEP_MORTGAGE" => Action::KeepMortgage {
+                position: parse(args, GameError::InvalidPosition)?,
+            },
+            "PAY_BAIL" => Action::PayBail,
+            "USE_JAIL_CARD" => Action::UseJailCard,
+            "READY" => Action::Ready,
+            "START" => Action::Start,
+            "KICK" => Action::Kick {
+                target: parse(args, GameError::UnknownPlayer)?,
+            },
+            "MUTE" => Action::Mute {
+                target: parse(args, GameError::UnknownPlayer)?,
+            },
+            "TRANSFER_HOST" => Action::TransferHost {
+                target: parse(args, GameError::UnknownPlayer)?,
+            },
+            "PAY_DEBT" => Action::PayDebt,
+            "BANKRUPT" => Action::Bankrupt,
+            _ => return Err(GameError::Malformed),
+        };
+
+        Ok(action)
+    }
+}
+
+/// Reads the next argument, failing with `error` if it is missing or does
+/// not parse.
+fn parse<'a, T: FromStr>(
+    args: &mut impl Iterator<Item = &'a str>,
+    error: GameError,
+) -> Result<T, GameError> {
+    args.next().and_then(|arg| arg.parse().ok()).ok_or(error)
+}
+
+/// Reads a trade bundle as three lines: cash, comma-separated deed
+/// positions (possibly empty) and a count of Get Out of Jail Free cards.
+fn parse_bundle<'a>(args: &mut impl Iterator<Item = &'a str>) -> Option<Bundle> {
+    let cash = args.next()?.parse().ok()?;
+
+    let deeds = match args.next()? {
+        "" => vec![],
+        deeds => deeds
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<usize>, _>>()
+            .ok()?,
+    };
+
+    let jail_cards = args.next()?.parse().ok()?;
+
+    Some(Bundle {
+        cash,
+        deeds,
+        jail_cards,
+    })
+}
+
+/// Reads a request from the fields of a JSON or `MessagePack` object. Fields
+/// that do not fit the command get the error the text protocol gives when
+/// they are left out.
+fn from_fields(fields: &Value) -> Result<Request, Rejected> {
+    Request::deserialize(fields).map_err(|_| Rejected {
+        nonce: fields["nonce"].as_str().unwrap_or("0").to_string(),
+        error: fields["command"]
+            .as_str()
+            .and_then(|command| Action::from_text(command, std::iter::empty()).err())
+            .unwrap_or(GameError::Malformed),
+    })
+}
+
+impl Protocol {
+    /// Subprotocols the server offers.
+    pub const SUPPORTED: [&'static str; 2] = ["monopoly.json", "monopoly.msgpack"];
+
+    /// Picks the first supported subprotocol the client asked for, or text
+    /// if it asked for none of them.
+    pub fn negotiate<'a>(mut offered: impl Iterator<Item = &'a str>) -> Protocol {
+        offered
+            .find_map(|name| match name {
+                "monopoly.json" => Some(Protocol::Json),
+                "monopoly.msgpack" => Some(Protocol::MsgPack),
+                _ => None,
+            })
+            .unwrap_or(Protocol::Text)
+    }
+
+    pub fn name(self) -> Option<&'static str> {
+        match self {
+            Protocol::Text => None,
+            Protocol::Json => Some("monopoly.json"),
+            Protocol::MsgPack => Some("monopoly.msgpack"),
+        }
+    }
+
+    /// Whether the protocol is carried in binary rather than text frames.
+    pub fn is_binary(self) -> bool {
+        self == Protocol::MsgPack
+    }
+
+    /// Reads a frame as a request. Text frames are `nonce\nCOMMAND\nargs...`;
+    /// the others encode a `Request` object.
+    pub fn parse(self, data: &[u8]) -> Result<Request, Rejected> {
+        let malformed = || Rejected {
+            nonce: "0".to_string(),
+            error: GameError::Malformed,
+        };
+
+        match self {
+            Protocol::Text => {
+                let mut lines = std::str::from_utf8(data).map_err(|_| malformed())?.lines();
+                let nonce = lines.next().ok_or_else(malformed)?.to_string();
+                let command = lines.next().unwrap_or_default();
+
+                match Action::from_text(command, lines) {
+                    Ok(action) => Ok(Request { nonce, action }),
+                    Err(error) => Err(Rejected { nonce, error }),
+                }
+            }
+            Protocol::Json => from_fields(&serde_json::from_slice(data).map_err(|_| malformed())?),
+            Protocol::MsgPack => {
+                from_fields(&rmp_serde::from_slice(data).map_err(|_| malformed())?)
+            }
+        }
+    }
+}
+
+/// The sending half of a connection along with the framing it speaks.
+#[derive(Clone)]
+pub struct Client {
+    sender: Arc<Mutex<Sender<UnixStream>>>,
+    protocol: Protocol,
+}
+
+impl Client {
+    pub fn new(sender: Arc<Mutex<Sender<UnixStream>>>, protocol: Protocol) -> Client {
+        Client { sender, protocol }
+    }
+
+    pub fn sender(&self) -> Arc<Mutex<Sender<UnixStream>>> {
+        self.sender.clone()
+    }
+
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    /// Answers a command with `text` on the text protocol, or `fields` as an
+    /// object on the others.
+    pub fn reply(&self, text: String, fields: &Value) {
+        match self.protocol {
+            Protocol::Text => util::sync!(self.sender.lock().send_text(text)).unwrap(),
+            _ => self.send(fields),
+        }
+    }
+
+    /// Sends `fields` as an object in JSON or `MessagePack`.
+    pub fn send(&self, fields: &Value) {
+        let mut sender = self.sender.lock();
+
+        if self.protocol.is_binary() {
+            util::sync!(sender.send_binary(rmp_serde::to_vec_named(fields).unwrap())).unwrap();
+        } else {
+            util::sync!(sender.send_text(fields.to_string())).unwrap();
+        }
+    }
+
+    pub fn success(&self, nonce: &str) {
+        self.reply(
+            format!("{nonce}\nSUCCESS"),
+            &json!({ "nonce": nonce, "status": "SUCCESS" }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_is_the_default() {
+        assert_eq!(Protocol::negotiate(std::iter::empty()), Protocol::Text);
+        assert_eq!(Protocol::negotiate(["chat"].into_iter()), Protocol::Text);
+        assert_eq!(Protocol::Text.name(), None);
+    }
+
+    #[test]
+    fn supported_protocols_are_negotiated_by_name() {
+        for name in Protocol::SUPPORTED {
+            let protocol = Protocol::negotiate([name].into_iter());
+
+            assert_eq!(protocol.name(), Some(name));
+        }
+
+        assert!(Protocol::negotiate(["monopoly.msgpack"].into_iter()).is_binary());
+        assert!(!Protocol::negotiate(["monopoly.json"].into_iter()).is_binary());
+    }
+
+    #[test]
+    fn unsupported_protocols_are_skipped() {
+        let offered = ["unknown", "monopoly.json", "monopoly.msgpack"];
+
+        assert_eq!(Protocol::negotiate(offered.into_iter()), Protocol::Json);
+        assert_eq!(
+            Protocol::negotiate(["chat", "unknown"].into_iter()),
+            Protocol::Text
+        );
+    }
+
+    #[test]
+    fn text_frames_are_split_into_lines() {
+        let request = Protocol::Text.parse(b"7\nBID\n120").unwrap();
+
+        assert_eq!(request.nonce, "7");
+        assert_eq!(request.action, Action::Bid { amount: 120 });
+        assert!(Protocol::Text.parse(b"").is_err());
+    }
+
+    #[test]
+    fn text_trade_bundles_are_three_lines() {
+        let request = Protocol::Text
+            .parse(b"7\nTRADE_PROPOSE\nplayer1\n100\n3,1\n0\n0\n\n1")
+            .unwrap();
+
+        let offer = Bundle {
+            cash: 100,
+            deeds: vec![3, 1],
+            jail_cards: 0,
+        };
+        let wanted = Bundle {
+            jail_cards: 1,
+            ..Bundle::default()
+        };
+
+        assert_eq!(
+            request.action,
+            Action::TradePropose {
+                target: "player1".to_string(),
+                offer,
+                request: wanted,
+            }
+        );
+    }
+
+    #[test]
+    fn bad_text_arguments_are_rejected_under_their_nonce() {
+        assert_eq!(
+            Protocol::Text.parse(b"7\nBID\nlots"),
+            Err(Rejected {
+                nonce: "7".to_string(),
+                error: GameError::InvalidBid,
+            })
+        );
+        assert_eq!(
+            Protocol::Text.parse(b"7\nDANCE").unwrap_err().error,
+            GameError::Malformed
+        );
+    }
+
+    #[test]
+    fn json_frames_are_request_objects() {
+        let request = Protocol::Json
+            .parse(br#"{"nonce": "7", "command": "ROLL"}"#)
+            .unwrap();
+
+        assert_eq!(request.nonce, "7");
+        assert_eq!(request.action, Action::Roll);
+        assert!(Protocol::Json.parse(b"7\nROLL").is_err());
+    }
+
+    #[test]
+    fn json_arguments_are_typed() {
+        let request = Protocol::Json
+            .parse(
+                br#"{
+                    "nonce": "7",
+                    "command": "TRADE_COUNTER",
+                    "id": 2,
+                    "offer": {"cash": 50, "deeds": [1, 3]},
+                    "request": {"jail_cards": 1}
+                }"#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            request.action,
+            Action::TradeCounter {
+                id: 2,
+                offer: Bundle {
+                    cash: 50,
+                    deeds: vec![1, 3],
+                    jail_cards: 0,
+                },
+                request: Bundle {
+                    jail_cards: 1,
+                    ..Bundle::default()
+                },
+            }
+        );
+
+        assert_eq!(
+            Protocol::Json.parse(br#"{"nonce": "8", "command": "BID", "amount": "120"}"#),
+            Err(Rejected {
+                nonce: "8".to_string(),
+                error: GameError::InvalidBid,
+            })
+        );
+    }
+
+    #[test]
+    fn msgpack_frames_are_request_objects() {
+        let frame = rmp_serde::to_vec_named(&json!({
+            "nonce": "7",
+            "command": "BID",
+            "amount": 120,
+        }))
+        .unwrap();
+
+        let request = Protocol::MsgPack.parse(&frame).unwrap();
+
+        assert_eq!(request.nonce, "7");
+        assert_eq!(request.action, Action::Bid { amount: 120 });
+        assert!(Protocol::MsgPack.parse(b"7\nBID").is_err());
+    }
+
+    #[test]
+    fn requests_keep_their_shape_through_json() {
+        let request = Request {
+            nonce: "7".to_string(),
+            action: Action::Init {
+                username: "alice".to_string(),
+                host_key: None,
+            },
+        };
+        let json = serde_json::to_vec(&request).unwrap();
+
+        assert_eq!(Protocol::Json.parse(&json), Ok(request));
+    }
+}
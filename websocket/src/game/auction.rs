@@ -0,0 +1,183 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::error::{GameError, Result};
+use crate::game::Session;
+
+pub const DEFAULT_AUCTION_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Auction {
+    id: usize,
+    pub(super) position: usize,
+    pub(super) highest: Option<(usize, u32)>,
+    #[serde(with = "remaining")]
+    pub(super) deadline: Instant,
+}
+
+/// Saves a deadline as the time left until it, since an `Instant` means
+/// nothing to another process.
+mod remaining {
+    use std::time::{Duration, Instant};
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(deadline: &Instant, serializer: S) -> Result<S::Ok, S::Error> {
+        deadline
+            .saturating_duration_since(Instant::now())
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Instant, D::Error> {
+        Ok(Instant::now() + Duration::deserialize(deserializer)?)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuctionResult {
+    pub position: usize,
+    /// Winning player and bid, or `None` if nobody bid and the deed stays with the bank.
+    pub winner: Option<(usize, u32)>,
+}
+
+impl Session {
+    /// Opens an auction for the deed at `position`, returning the auction id
+    /// the closing timer should watch.
+    pub(super) fn start_auction(&mut self, position: usize) -> usize {
+        self.auctions += 1;
+        self.auction = Some(Auction {
+            id: self.auctions,
+            position,
+            highest: None,
+            deadline: Instant::now() + self.auction_timeout,
+        });
+
+        self.auctions
+    }
+
+    pub fn auction_timeout(&self) -> Duration {
+        self.auction_timeout
+    }
+
+    /// Applies to auctions started from now on.
+    pub fn set_auction_timeout(&mut self, timeout: Duration) {
+        self.auction_timeout = timeout;
+    }
+
+    pub fn bid(&mut self, player_id: usize, amount: u32) -> Result<()> {
+        let Some(auction) = &mut self.auction else {
+            return Err(GameError::NoAuction);
+        };
+
+        let player = &self.players[player_id];
+
+        if player.bankrupt {
+            return Err(GameError::BidderBankrupt);
+        }
+
+        if auction
+            .highest
+            .is_some_and(|(_, highest)| amount <= highest)
+            || amount == 0
+        {
+            return Err(GameError::BidTooLow);
+        }
+
+        if amount > player.cash {
+            return Err(GameError::InsufficientFunds);
+        }
+
+        auction.highest = Some((player_id, amount));
+        auction.deadline = Instant::now() + self.auction_timeout;
+
+        Ok(())
+    }
+
+    /// Deed up for auction, if one is running.
+    pub fn auction_position(&self) -> Option<usize> {
+        self.auction.as_ref().map(|auction| auction.position)
+    }
+
+    /// Id of the running auction, for restarting its closing timer.
+    pub fn auction_id(&self) -> Option<usize> {
+        self.auction.as_ref().map(|auction| auction.id)
+    }
+
+    /// Deadline of auction `id`, or `None` once it has been closed.
+    pub fn auction_deadline(&self, id: usize) -> Option<Instant> {
+        self.auction
+            .as_ref()
+            .filter(|auction| auction.id == id)
+            .map(|auction| auction.deadline)
+    }
+
+    /// Ends the running auction, handing the deed to the highest bidder.
+    pub fn close_auction(&mut self) -> Option<AuctionResult> {
+        let auction = self.auction.take()?;
+        let mut result = AuctionResult {
+            position: auction.position,
+            winner: None,
+        };
+
+        if let Some((winner, amount)) = auction.highest {
+            let player = &mut self.players[winner];
+
+            // Cash may have changed hands since the bid was placed.
+            if player.cash >= amount {
+                player.cash -= amount;
+                player.deeds.push(auction.position);
+                result.winner = Some((winner, amount));
+            }
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use house_rules::STARTING_CASH;
+
+    const MEDITERRANEAN: usize = 1;
+
+    #[test]
+    fn highest_bidder_wins_the_deed() {
+        let mut session = Session::playing(3);
+        session.start_auction(MEDITERRANEAN);
+
+        session.bid(1, 10).unwrap();
+        assert_eq!(session.bid(2, 10).unwrap_err(), GameError::BidTooLow);
+        session.bid(2, 20).unwrap();
+
+        let result = session.close_auction().unwrap();
+        assert_eq!(result.winner, Some((2, 20)));
+        assert_eq!(session.owner_of(MEDITERRANEAN), Some(2));
+        assert_eq!(session.players[2].cash, STARTING_CASH - 20);
+        assert!(session.close_auction().is_none());
+    }
+
+    #[test]
+    fn bids_must_be_positive_and_covered() {
+        let mut session = Session::playing(2);
+        assert_eq!(session.bid(1, 10).unwrap_err(), GameError::NoAuction);
+
+        session.start_auction(MEDITERRANEAN);
+        assert_eq!(session.bid(1, 0).unwrap_err(), GameError::BidTooLow);
+        assert_eq!(
+            session.bid(1, STARTING_CASH + 1).unwrap_err(),
+            GameError::InsufficientFunds
+        );
+    }
+
+    #[test]
+    fn deed_stays_with_the_bank_without_bids() {
+        let mut session = Session::playing(2);
+        session.start_auction(MEDITERRANEAN);
+
+        let result = session.close_auction().unwrap();
+        assert_eq!(result.winner, None);
+        assert_eq!(session.owner_of(MEDITERRANEAN), None);
+    }
+}
@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::building::HOTEL;
+use crate::game::error::{GameError, Result};
+use crate::game::{Outcome, Session};
+
+/// Money a player could not cover; `creditor` is `None` when the bank is owed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Debt {
+    pub debtor: usize,
+    pub creditor: Option<usize>,
+    pub amount: u32,
+}
+
+impl Session {
+    /// Whether `player_id` is raising funds to cover a debt.
+    pub fn in_debt(&self, player_id: usize) -> bool {
+        self.debts.iter().any(|debt| debt.debtor == player_id)
+    }
+
+    /// Settles every outstanding debt of `player_id` at once.
+    pub fn pay_debts(&mut self, player_id: usize) -> Result<Vec<Outcome>> {
+        let debts = self.take_debts(player_id);
+
+        if debts.is_empty() {
+            return Err(GameError::NoDebt);
+        }
+
+        let owed = debts.iter().map(|debt| debt.amount).sum::<u32>();
+        if self.players[player_id].cash < owed {
+            self.debts.extend(debts);
+            return Err(GameError::InsufficientFunds);
+        }
+
+        let mut outcomes = vec![];
+        for debt in debts {
+            self.players[player_id].cash -= debt.amount;
+            match debt.creditor {
+                Some(creditor) => self.players[creditor].cash += debt.amount,
+                None => self.bank_receives(debt.amount),
+            }
+
+            outcomes.push(Outcome::Transfer {
+                from: Some(player_id),
+                to: debt.creditor,
+                amount: debt.amount,
+            });
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Gives up the game. Everything goes to the creditor if a single player
+    /// is owed; otherwise buildings return to the bank and the deeds are
+    /// auctioned off. Also returns the id of an auction that was started.
+    pub fn declare_bankruptcy(
+        &mut self,
+        player_id: usize,
+    ) -> Result<(Vec<Outcome>, Option<usize>)> {
+        let debts = self.take_debts(player_id);
+
+        if debts.is_empty() {
+            return Err(GameError::NoDebt);
+        }
+
+        let creditor = debts[0]
+            .creditor
+            .filter(|creditor| debts.iter().all(|debt| debt.creditor == Some(*creditor)));
+
+        let mut outcomes = vec![Outcome::Bankrupt {
+            player: player_id,
+            creditor,
+        }];
+        let (mut retired, auction) = self.retire(player_id, creditor);
+        outcomes.append(&mut retired);
+
+        Ok((outcomes, auction))
+    }
+
+    /// Takes `player_id` out of the game, handing everything they hold to
+    /// `creditor` or the bank, and moves the game along if need be. Once a
+    /// single player is left standing the game is over and the turn stays put.
+    pub(super) fn retire(
+        &mut self,
+        player_id: usize,
+        creditor: Option<usize>,
+    ) -> (Vec<Outcome>, Option<usize>) {
+        let auction = self.liquidate(player_id, creditor);
+        let mut outcomes = vec![];
+
+        if !self.started || self.winner.is_some() {
+            return (outcomes, auction);
+        }
+
+        let mut solvent = self.players.iter().filter(|player| !player.bankrupt);
+        if let (Some(winner), None) = (solvent.next(), solvent.next()) {
+            self.winner = Some(winner.id);
+            outcomes.push(Outcome::GameOver { winner: winner.id });
+
+            return (outcomes, auction);
+        }
+
+        if self.turn.player == player_id {
+            let next = self.pass_turn();
+            outcomes.push(Outcome::Turn { player: next });
+        }
+
+        (outcomes, auction)
+    }
+
+    /// Starts the next queued auction if none is running, returning its id.
+    pub fn next_auction(&mut self) -> Option<usize> {
+        if self.auction.is_some() {
+            return None;
+        }
+
+        let position = self.auction_queue.pop_front()?;
+
+        Some(self.start_auction(position))
+    }
+
+    fn liquidate(&mut self, player_id: usize, creditor: Option<usize>) -> Option<usize> {
+        let player = &mut self.players[player_id];
+        let deeds = std::mem::take(&mut player.deeds);
+        let jail_cards = std::mem::take(&mut player.jail_cards);
+        let mut cash = std::mem::take(&mut player.cash);
+        player.bankrupt = true;
+
+        // Buildings always go back to the bank at half price.
+        for deed in &deeds {
+            let buildings = std::mem::take(&mut self.properties[*deed].buildings);
+
+            if buildings == HOTEL {
+                self.hotels += 1;
+            } else {
+                self.houses += buildings;
+            }
+
+            if let Some(cost) = self.board.house_cost(*deed) {
+                cash += u32::from(buildings) * cost / 2;
+            }
+        }
+
+        self.trades
+            .retain(|trade| trade.proposer != player_id && trade.target != player_id);
+        for debt in &mut self.debts {
+            if debt.creditor == Some(player_id) {
+                debt.creditor = creditor;
+            }
+        }
+        self.mortgage_decisions
+            .retain(|(owner, _)| *owner != player_id);
+
+        if let Some(creditor) = creditor {
+            self.players[creditor].cash += cash;
+            self.players[creditor].jail_cards.extend(jail_cards);
+
+            for deed in deeds {
+                self.receive_deed(creditor, deed);
+            }
+
+            return None;
+        }
+
+        for deck in jail_cards {
+            self.return_jail_card(deck);
+        }
+
+        for deed in &deeds {
+            self.properties[*deed].mortgaged = false;
+        }
+        if self.rules.auctions {
+            self.auction_queue.extend(deeds);
+        }
+
+        self.next_auction()
+    }
+
+    fn take_debts(&mut self, player_id: usize) -> Vec<Debt> {
+        let (debts, others) = std::mem::take(&mut self.debts)
+            .into_iter()
+            .partition(|debt| debt.debtor == player_id);
+        self.debts = others;
+
+        debts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::building::HOUSE_SUPPLY;
+    use house_rules::STARTING_CASH;
+
+    const MEDITERRANEAN: usize = 1;
+    const BALTIC: usize = 3;
+    const READING_RAILROAD: usize = 5;
+
+    /// A game of `count` where player 0 owes `creditor` more than they have.
+    fn indebted(count: usize, creditor: Option<usize>) -> Session {
+        let mut session = Session::playing(count);
+        session.debts.push(Debt {
+            debtor: 0,
+            creditor,
+            amount: STARTING_CASH + 1,
+        });
+
+        session
+    }
+
+    #[test]
+    fn debts_are_paid_in_full_or_not_at_all() {
+        let mut session = indebted(2, Some(1));
+        assert!(session.in_debt(0));
+
+        assert_eq!(
+            session.pay_debts(0).unwrap_err(),
+            GameError::InsufficientFunds
+        );
+        assert!(session.in_debt(0));
+
+        session.players[0].cash += 1;
+        session.pay_debts(0).unwrap();
+        assert!(!session.in_debt(0));
+        assert_eq!(session.players[0].cash, 0);
+        assert_eq!(session.players[1].cash, 2 * STARTING_CASH + 1);
+        assert_eq!(session.pay_debts(0).unwrap_err(), GameError::NoDebt);
+    }
+
+    #[test]
+    fn a_single_creditor_takes_everything() {
+        let mut session = indebted(3, Some(1));
+        session.players[0]
+            .deeds
+            .extend([MEDITERRANEAN, BALTIC, READING_RAILROAD]);
+        session.properties[MEDITERRANEAN].buildings = 1;
+        session.properties[READING_RAILROAD].mortgaged = true;
+        session.houses -= 1;
+
+        let (outcomes, auction) = session.declare_bankruptcy(0).unwrap();
+
+        assert!(matches!(
+            outcomes[0],
+            Outcome::Bankrupt {
+                player: 0,
+                creditor: Some(1)
+            }
+        ));
+        assert_eq!(auction, None);
+        assert!(session.players[0].bankrupt);
+        // The house goes back to the bank at half its $50 cost.
+        assert_eq!(session.players[1].cash, 2 * STARTING_CASH + 25);
+        assert_eq!(session.houses, HOUSE_SUPPLY);
+        assert_eq!(
+            session.players[1].deeds,
+            vec![MEDITERRANEAN, BALTIC, READING_RAILROAD]
+        );
+        assert_eq!(session.mortgage_decisions(1), vec![READING_RAILROAD]);
+    }
+
+    #[test]
+    fn deeds_owed_to_the_bank_are_auctioned() {
+        let mut session = indebted(3, None);
+        session.players[0]
+            .deeds
+            .extend([MEDITERRANEAN, READING_RAILROAD]);
+        session.properties[READING_RAILROAD].mortgaged = true;
+
+        let (_, auction) = session.declare_bankruptcy(0).unwrap();
+
+        assert!(auction.is_some());
+        assert_eq!(session.owner_of(MEDITERRANEAN), None);
+        assert_eq!(session.auction_queue, [READING_RAILROAD]);
+        assert!(!session.properties[READING_RAILROAD].mortgaged);
+    }
+
+    #[test]
+    fn last_solvent_player_wins() {
+        let mut session = indebted(2, Some(1));
+
+        let (outcomes, _) = session.declare_bankruptcy(0).unwrap();
+
+        assert!(outcomes
+            .iter()
+            .any(|outcome| matches!(outcome, Outcome::GameOver { winner: 1 })));
+    }
+}
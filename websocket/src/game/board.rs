@@ -0,0 +1,220 @@
+pub const BOARD_SIZE: usize = 40;
+pub const JAIL_POSITION: usize = 10;
+pub const GO_SALARY: u32 = 200;
+
+const RAILROAD_RENT: [u32; 4] = [25, 50, 100, 200];
+const UTILITY_MULTIPLIER: [u32; 2] = [4, 10];
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ColorGroup {
+    Brown,
+    LightBlue,
+    Pink,
+    Orange,
+    Red,
+    Yellow,
+    Green,
+    DarkBlue,
+}
+
+impl ColorGroup {
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorGroup::Brown => "BROWN",
+            ColorGroup::LightBlue => "LIGHT_BLUE",
+            ColorGroup::Pink => "PINK",
+            ColorGroup::Orange => "ORANGE",
+            ColorGroup::Red => "RED",
+            ColorGroup::Yellow => "YELLOW",
+            ColorGroup::Green => "GREEN",
+            ColorGroup::DarkBlue => "DARK_BLUE",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Street {
+    pub name: &'static str,
+    pub group: ColorGroup,
+    pub price: u32,
+    /// Rent with 0 through 4 houses, followed by rent with a hotel.
+    pub rent: [u32; 6],
+    pub house_cost: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Railroad {
+    pub name: &'static str,
+    pub price: u32,
+}
+
+impl Railroad {
+    /// Rent owed when the owner holds `owned` railroads (1 through 4).
+    pub fn rent(owned: usize) -> u32 {
+        RAILROAD_RENT[owned.clamp(1, RAILROAD_RENT.len()) - 1]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Utility {
+    pub name: &'static str,
+    pub price: u32,
+}
+
+impl Utility {
+    /// Dice multiplier owed when the owner holds `owned` utilities (1 or 2).
+    pub fn multiplier(owned: usize) -> u32 {
+        UTILITY_MULTIPLIER[owned.clamp(1, UTILITY_MULTIPLIER.len()) - 1]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Space {
+    Go,
+    Street(Street),
+    Railroad(Railroad),
+    Utility(Utility),
+    Tax { name: &'static str, amount: u32 },
+    Chance,
+    CommunityChest,
+    Jail,
+    FreeParking,
+    GoToJail,
+}
+
+impl Space {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Space::Go => "Go",
+            Space::Street(Street { name, .. })
+            | Space::Railroad(Railroad { name, .. })
+            | Space::Utility(Utility { name, .. })
+            | Space::Tax { name, .. } => name,
+            Space::Chance => "Chance",
+            Space::CommunityChest => "Community Chest",
+            Space::Jail => "Jail",
+            Space::FreeParking => "Free Parking",
+            Space::GoToJail => "Go To Jail",
+        }
+    }
+
+    /// Purchase price of the deed, or `None` if the space cannot be owned.
+    pub fn price(&self) -> Option<u32> {
+        match self {
+            Space::Street(Street { price, .. })
+            | Space::Railroad(Railroad { price, .. })
+            | Space::Utility(Utility { price, .. }) => Some(*price),
+            _ => None,
+        }
+    }
+
+    pub fn mortgage_value(&self) -> Option<u32> {
+        self.price().map(|price| price / 2)
+    }
+
+    pub fn is_property(&self) -> bool {
+        self.price().is_some()
+    }
+}
+
+#[derive(Debug)]
+pub struct Board {
+    spaces: Vec<Space>,
+}
+
+impl Board {
+    pub fn new() -> Board {
+        use ColorGroup::{Brown, DarkBlue, Green, LightBlue, Orange, Pink, Red, Yellow};
+
+        let street = |name, group, price, rent, house_cost| {
+            Space::Street(Street {
+                name,
+                group,
+                price,
+                rent,
+                house_cost,
+            })
+        };
+        let railroad = |name| Space::Railroad(Railroad { name, price: 200 });
+        let utility = |name| Space::Utility(Utility { name, price: 150 });
+
+        #[rustfmt::skip]
+        let spaces = vec![
+            Space::Go,
+            street("Mediterranean Avenue", Brown, 60, [2, 10, 30, 90, 160, 250], 50),
+            Space::CommunityChest,
+            street("Baltic Avenue", Brown, 60, [4, 20, 60, 180, 320, 450], 50),
+            Space::Tax { name: "Income Tax", amount: 200 },
+            railroad("Reading Railroad"),
+            street("Oriental Avenue", LightBlue, 100, [6, 30, 90, 270, 400, 550], 50),
+            Space::Chance,
+            street("Vermont Avenue", LightBlue, 100, [6, 30, 90, 270, 400, 550], 50),
+            street("Connecticut Avenue", LightBlue, 120, [8, 40, 100, 300, 450, 600], 50),
+            Space::Jail,
+            street("St. Charles Place", Pink, 140, [10, 50, 150, 450, 625, 750], 100),
+            utility("Electric Company"),
+            street("States Avenue", Pink, 140, [10, 50, 150, 450, 625, 750], 100),
+            street("Virginia Avenue", Pink, 160, [12, 60, 180, 500, 700, 900], 100),
+            railroad("Pennsylvania Railroad"),
+            street("St. James Place", Orange, 180, [14, 70, 200, 550, 750, 950], 100),
+            Space::CommunityChest,
+            street("Tennessee Avenue", Orange, 180, [14, 70, 200, 550, 750, 950], 100),
+            street("New York Avenue", Orange, 200, [16, 80, 220, 600, 800, 1000], 100),
+            Space::FreeParking,
+            street("Kentucky Avenue", Red, 220, [18, 90, 250, 700, 875, 1050], 150),
+            Space::Chance,
+            street("Indiana Avenue", Red, 220, [18, 90, 250, 700, 875, 1050], 150),
+            street("Illinois Avenue", Red, 240, [20, 100, 300, 750, 925, 1100], 150),
+            railroad("B. & O. Railroad"),
+            street("Atlantic Avenue", Yellow, 260, [22, 110, 330, 800, 975, 1150], 150),
+            street("Ventnor Avenue", Yellow, 260, [22, 110, 330, 800, 975, 1150], 150),
+            utility("Water Works"),
+            street("Marvin Gardens", Yellow, 280, [24, 120, 360, 850, 1025, 1200], 150),
+            Space::GoToJail,
+            street("Pacific Avenue", Green, 300, [26, 130, 390, 900, 1100, 1275], 200),
+            street("North Carolina Avenue", Green, 300, [26, 130, 390, 900, 1100, 1275], 200),
+            Space::CommunityChest,
+            street("Pennsylvania Avenue", Green, 320, [28, 150, 450, 1000, 1200, 1400], 200),
+            railroad("Short Line"),
+            Space::Chance,
+            street("Park Place", DarkBlue, 350, [35, 175, 500, 1100, 1300, 1500], 200),
+            Space::Tax { name: "Luxury Tax", amount: 100 },
+            street("Boardwalk", DarkBlue, 400, [50, 200, 600, 1400, 1700, 2000], 200),
+        ];
+
+        debug_assert_eq!(spaces.len(), BOARD_SIZE);
+
+        Board { spaces }
+    }
+
+    pub fn space(&self, position: usize) -> &Space {
+        &self.spaces[position % BOARD_SIZE]
+    }
+
+    pub fn house_cost(&self, position: usize) -> Option<u32> {
+        match self.space(position) {
+            Space::Street(street) => Some(street.house_cost),
+            _ => None,
+        }
+    }
+
+    pub fn spaces(&self) -> &Vec<Space> {
+        &self.spaces
+    }
+
+    /// Board positions of every street in `group`.
+    pub fn group_positions(&self, group: ColorGroup) -> Vec<usize> {
+        self.spaces
+            .iter()
+            .enumerate()
+            .filter(|(_, space)| matches!(space, Space::Street(street) if street.group == group))
+            .map(|(position, _)| position)
+            .collect()
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
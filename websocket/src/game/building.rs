@@ -0,0 +1,230 @@
+use crate::game::board::{Space, Street};
+use crate::game::error::{GameError, Result};
+use crate::game::Session;
+
+pub const HOUSE_SUPPLY: u8 = 32;
+pub const HOTEL_SUPPLY: u8 = 12;
+
+/// Building count that stands for a hotel (four houses traded in).
+pub const HOTEL: u8 = 5;
+
+impl Session {
+    /// Adds a house, or a hotel on top of four houses, returning the new
+    /// building count of the street.
+    pub fn build(&mut self, player_id: usize, position: usize) -> Result<u8> {
+        let street = self.buildable_street(player_id, position)?;
+        let buildings = self.properties[position].buildings;
+
+        if buildings == HOTEL {
+            return Err(GameError::BuildingLimit);
+        }
+
+        if self.rules.even_build && self.group_buildings(&street).any(|other| other < buildings) {
+            return Err(GameError::UnevenBuild);
+        }
+
+        if buildings == HOTEL - 1 {
+            if self.hotels == 0 {
+                return Err(GameError::BankOutOfBuildings);
+            }
+        } else if self.houses == 0 {
+            return Err(GameError::BankOutOfBuildings);
+        }
+
+        if self.players[player_id].cash < street.house_cost {
+            return Err(GameError::InsufficientFunds);
+        }
+
+        if buildings == HOTEL - 1 {
+            self.hotels -= 1;
+            self.houses += HOTEL - 1;
+        } else {
+            self.houses -= 1;
+        }
+
+        self.players[player_id].cash -= street.house_cost;
+        self.properties[position].buildings += 1;
+
+        Ok(buildings + 1)
+    }
+
+    /// Sells a building back to the bank at half price, returning the new
+    /// building count of the street and the refund.
+    pub fn sell_building(&mut self, player_id: usize, position: usize) -> Result<(u8, u32)> {
+        let street = self.buildable_street(player_id, position)?;
+        let buildings = self.properties[position].buildings;
+
+        if buildings == 0 {
+            return Err(GameError::BuildingLimit);
+        }
+
+        if self.rules.even_build && self.group_buildings(&street).any(|other| other > buildings) {
+            return Err(GameError::UnevenBuild);
+        }
+
+        if buildings == HOTEL {
+            // The hotel is swapped back for the four houses it replaced.
+            if self.houses < HOTEL - 1 {
+                return Err(GameError::BankOutOfBuildings);
+            }
+
+            self.houses -= HOTEL - 1;
+            self.hotels += 1;
+        } else {
+            self.houses += 1;
+        }
+
+        let refund = street.house_cost / 2;
+        self.players[player_id].cash += refund;
+        self.properties[position].buildings -= 1;
+
+        Ok((buildings - 1, refund))
+    }
+
+    /// Whether any street in the color group of `position` has buildings.
+    pub fn group_has_buildings(&self, position: usize) -> bool {
+        match self.board.space(position) {
+            Space::Street(street) => self.group_buildings(street).any(|buildings| buildings > 0),
+            _ => false,
+        }
+    }
+
+    fn buildable_street(&self, player_id: usize, position: usize) -> Result<Street> {
+        let Space::Street(street) = self.board.space(position) else {
+            return Err(GameError::NotOwned);
+        };
+
+        if self.owner_of(position) != Some(player_id) {
+            return Err(GameError::NotOwned);
+        }
+
+        if !self.owns_group(player_id, street.group) {
+            return Err(GameError::IncompleteSet);
+        }
+
+        let mortgaged = self
+            .board
+            .group_positions(street.group)
+            .into_iter()
+            .any(|position| self.properties[position].mortgaged);
+
+        if mortgaged {
+            return Err(GameError::MortgageState);
+        }
+
+        Ok(street.clone())
+    }
+
+    fn group_buildings<'a>(&'a self, street: &Street) -> impl Iterator<Item = u8> + 'a {
+        self.board
+            .group_positions(street.group)
+            .into_iter()
+            .map(|position| self.properties[position].buildings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use house_rules::STARTING_CASH;
+
+    const MEDITERRANEAN: usize = 1;
+    const BALTIC: usize = 3;
+
+    /// A game where player 0 owns the brown set.
+    fn brown_set() -> Session {
+        let mut session = Session::playing(2);
+        session.players[0].deeds.extend([MEDITERRANEAN, BALTIC]);
+
+        session
+    }
+
+    #[test]
+    fn houses_need_the_full_set() {
+        let mut session = Session::playing(2);
+        session.players[0].deeds.push(MEDITERRANEAN);
+
+        assert_eq!(
+            session.build(0, MEDITERRANEAN).unwrap_err(),
+            GameError::IncompleteSet
+        );
+    }
+
+    #[test]
+    fn houses_are_built_and_sold_evenly() {
+        let mut session = brown_set();
+
+        assert_eq!(session.build(0, MEDITERRANEAN).unwrap(), 1);
+        assert_eq!(
+            session.build(0, MEDITERRANEAN).unwrap_err(),
+            GameError::UnevenBuild
+        );
+        assert_eq!(session.build(0, BALTIC).unwrap(), 1);
+        assert_eq!(session.build(0, MEDITERRANEAN).unwrap(), 2);
+
+        assert_eq!(
+            session.sell_building(0, BALTIC).unwrap_err(),
+            GameError::UnevenBuild
+        );
+        assert_eq!(session.sell_building(0, MEDITERRANEAN).unwrap(), (1, 25));
+    }
+
+    #[test]
+    fn uneven_building_is_allowed_when_the_rule_is_off() {
+        let mut session = brown_set();
+        session.rules.even_build = false;
+
+        assert_eq!(session.build(0, MEDITERRANEAN).unwrap(), 1);
+        assert_eq!(session.build(0, MEDITERRANEAN).unwrap(), 2);
+    }
+
+    #[test]
+    fn hotels_trade_in_four_houses() {
+        let mut session = brown_set();
+        assert_eq!((session.houses, session.hotels), (32, 12));
+
+        for _ in 0..4 {
+            session.build(0, MEDITERRANEAN).unwrap();
+            session.build(0, BALTIC).unwrap();
+        }
+        assert_eq!(session.houses, 24);
+
+        assert_eq!(session.build(0, MEDITERRANEAN).unwrap(), HOTEL);
+        assert_eq!((session.houses, session.hotels), (28, 11));
+        assert_eq!(
+            session.build(0, MEDITERRANEAN).unwrap_err(),
+            GameError::BuildingLimit
+        );
+
+        assert_eq!(session.sell_building(0, MEDITERRANEAN).unwrap(), (4, 25));
+        assert_eq!((session.houses, session.hotels), (24, 12));
+    }
+
+    #[test]
+    fn bank_runs_out_of_buildings() {
+        let mut session = brown_set();
+        session.houses = 0;
+
+        assert_eq!(
+            session.build(0, MEDITERRANEAN).unwrap_err(),
+            GameError::BankOutOfBuildings
+        );
+
+        session.properties[MEDITERRANEAN].buildings = 4;
+        session.properties[BALTIC].buildings = 4;
+        session.hotels = 0;
+
+        assert_eq!(
+            session.build(0, MEDITERRANEAN).unwrap_err(),
+            GameError::BankOutOfBuildings
+        );
+
+        // Breaking up a hotel needs four houses in the bank.
+        session.properties[MEDITERRANEAN].buildings = HOTEL;
+        assert_eq!(
+            session.sell_building(0, MEDITERRANEAN).unwrap_err(),
+            GameError::BankOutOfBuildings
+        );
+        assert_eq!(session.players[0].cash, STARTING_CASH);
+    }
+}
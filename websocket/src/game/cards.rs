@@ -0,0 +1,356 @@
+use std::collections::VecDeque;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::game::board::{Space, BOARD_SIZE};
+use crate::game::building::HOTEL;
+use crate::game::property::RentRule;
+use crate::game::{Outcome, Session};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Deck {
+    Chance,
+    CommunityChest,
+}
+
+impl Deck {
+    pub fn name(self) -> &'static str {
+        match self {
+            Deck::Chance => "CHANCE",
+            Deck::CommunityChest => "COMMUNITY_CHEST",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Effect {
+    /// Advance to a position, collecting the salary when passing Go.
+    MoveTo(usize),
+    Back(usize),
+    /// Advance to the nearest railroad and pay double rent if it is owned.
+    NearestRailroad,
+    /// Advance to the nearest utility and pay ten times a fresh roll if it is owned.
+    NearestUtility,
+    Collect(u32),
+    Pay(u32),
+    PayEach(u32),
+    CollectEach(u32),
+    Repairs {
+        house: u32,
+        hotel: u32,
+    },
+    JailCard,
+    GoToJail,
+}
+
+#[derive(Debug, Clone)]
+pub struct Card {
+    pub text: &'static str,
+    pub effect: Effect,
+}
+
+const JAIL_CARD: Card = Card {
+    text: "Get Out of Jail Free. This card may be kept until needed or traded.",
+    effect: Effect::JailCard,
+};
+
+#[rustfmt::skip]
+const CHANCE: [Card; 16] = [
+    Card { text: "Advance to Boardwalk.", effect: Effect::MoveTo(39) },
+    Card { text: "Advance to Go. Collect $200.", effect: Effect::MoveTo(0) },
+    Card { text: "Advance to Illinois Avenue. If you pass Go, collect $200.", effect: Effect::MoveTo(24) },
+    Card { text: "Advance to St. Charles Place. If you pass Go, collect $200.", effect: Effect::MoveTo(11) },
+    Card { text: "Advance to the nearest Railroad. If owned, pay the owner twice the rental to which they are otherwise entitled.", effect: Effect::NearestRailroad },
+    Card { text: "Advance to the nearest Railroad. If owned, pay the owner twice the rental to which they are otherwise entitled.", effect: Effect::NearestRailroad },
+    Card { text: "Advance to the nearest Utility. If owned, throw the dice and pay the owner ten times the amount thrown.", effect: Effect::NearestUtility },
+    Card { text: "Bank pays you dividend of $50.", effect: Effect::Collect(50) },
+    JAIL_CARD,
+    Card { text: "Go back 3 spaces.", effect: Effect::Back(3) },
+    Card { text: "Go to Jail. Go directly to Jail, do not pass Go, do not collect $200.", effect: Effect::GoToJail },
+    Card { text: "Make general repairs on all your property. For each house pay $25. For each hotel pay $100.", effect: Effect::Repairs { house: 25, hotel: 100 } },
+    Card { text: "Speeding fine $15.", effect: Effect::Pay(15) },
+    Card { text: "Take a trip to Reading Railroad. If you pass Go, collect $200.", effect: Effect::MoveTo(5) },
+    Card { text: "You have been elected Chairman of the Board. Pay each player $50.", effect: Effect::PayEach(50) },
+    Card { text: "Your building loan matures. Collect $150.", effect: Effect::Collect(150) },
+];
+
+#[rustfmt::skip]
+const COMMUNITY_CHEST: [Card; 16] = [
+    Card { text: "Advance to Go. Collect $200.", effect: Effect::MoveTo(0) },
+    Card { text: "Bank error in your favor. Collect $200.", effect: Effect::Collect(200) },
+    Card { text: "Doctor's fee. Pay $50.", effect: Effect::Pay(50) },
+    Card { text: "From sale of stock you get $50.", effect: Effect::Collect(50) },
+    JAIL_CARD,
+    Card { text: "Go to Jail. Go directly to Jail, do not pass Go, do not collect $200.", effect: Effect::GoToJail },
+    Card { text: "Holiday fund matures. Receive $100.", effect: Effect::Collect(100) },
+    Card { text: "Income tax refund. Collect $20.", effect: Effect::Collect(20) },
+    Card { text: "It is your birthday. Collect $10 from every player.", effect: Effect::CollectEach(10) },
+    Card { text: "Life insurance matures. Collect $100.", effect: Effect::Collect(100) },
+    Card { text: "Pay hospital fees of $100.", effect: Effect::Pay(100) },
+    Card { text: "Pay school fees of $50.", effect: Effect::Pay(50) },
+    Card { text: "Receive $25 consultancy fee.", effect: Effect::Collect(25) },
+    Card { text: "You are assessed for street repair. $40 per house. $115 per hotel.", effect: Effect::Repairs { house: 40, hotel: 115 } },
+    Card { text: "You have won second prize in a beauty contest. Collect $10.", effect: Effect::Collect(10) },
+    Card { text: "You inherit $100.", effect: Effect::Collect(100) },
+];
+
+/// Cards are saved by their text, which is unique within the decks.
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Card, D::Error> {
+        let text = String::deserialize(deserializer)?;
+
+        CHANCE
+            .iter()
+            .chain(COMMUNITY_CHEST.iter())
+            .find(|card| card.text == text)
+            .cloned()
+            .ok_or_else(|| D::Error::custom(format!("unknown card: {text}")))
+    }
+}
+
+/// The cards of `deck`, shuffled with the game's `rng`.
+pub fn shuffled(deck: Deck, rng: &mut StdRng) -> VecDeque<Card> {
+    let mut cards = match deck {
+        Deck::Chance => CHANCE.to_vec(),
+        Deck::CommunityChest => COMMUNITY_CHEST.to_vec(),
+    };
+    cards.shuffle(rng);
+
+    cards.into()
+}
+
+impl Session {
+    /// Returns a held Get Out of Jail Free card to the bottom of its deck.
+    pub(super) fn return_jail_card(&mut self, deck: Deck) {
+        self.deck_mut(deck).push_back(JAIL_CARD);
+    }
+
+    /// Draws the top card of `deck` for `player_id` and applies it. Cards go
+    /// back to the bottom of the deck, except Get Out of Jail Free cards,
+    /// which stay with the player until used.
+    pub(super) fn draw(&mut self, player_id: usize, deck: Deck, dice: usize) -> Vec<Outcome> {
+        let card = self.deck_mut(deck).pop_front().unwrap();
+        let mut outcomes = vec![Outcome::Card {
+            player: player_id,
+            deck,
+            text: card.text,
+        }];
+
+        if let Effect::JailCard = card.effect {
+            self.players[player_id].jail_cards.push(deck);
+        } else {
+            self.deck_mut(deck).push_back(card.clone());
+        }
+
+        let position = self.players[player_id].position;
+
+        match card.effect {
+            Effect::MoveTo(target) => {
+                outcomes.append(&mut self.advance(player_id, target, RentRule::Standard { dice }));
+            }
+            Effect::Back(spaces) => {
+                let target = (position + BOARD_SIZE - spaces) % BOARD_SIZE;
+                self.players[player_id].position = target;
+
+                outcomes.push(Outcome::Move {
+                    player: player_id,
+                    position: target,
+                });
+                outcomes.append(&mut self.land(player_id, RentRule::Standard { dice }));
+            }
+            Effect::NearestRailroad => {
+                let target = self.nearest(position, |space| matches!(space, Space::Railroad(_)));
+                outcomes.append(&mut self.advance(player_id, target, RentRule::DoubleRailroad));
+            }
+            Effect::NearestUtility => {
+                let target = self.nearest(position, |space| matches!(space, Space::Utility(_)));
+                let dice = self.rng.gen_range(1..=6) + self.rng.gen_range(1..=6);
+                outcomes.append(&mut self.advance(
+                    player_id,
+                    target,
+                    RentRule::TenTimesDice { dice },
+                ));
+            }
+            Effect::Collect(amount) => {
+                self.players[player_id].cash += amount;
+                outcomes.push(Outcome::Transfer {
+                    from: None,
+                    to: Some(player_id),
+                    amount,
+                });
+            }
+            Effect::Pay(amount) => {
+                outcomes.push(self.transfer(player_id, None, amount));
+            }
+            Effect::PayEach(amount) => {
+                for other in self.opponents(player_id) {
+                    outcomes.push(self.transfer(player_id, Some(other), amount));
+                }
+            }
+            Effect::CollectEach(amount) => {
+                for other in self.opponents(player_id) {
+                    outcomes.push(self.transfer(other, Some(player_id), amount));
+                }
+            }
+            Effect::Repairs { house, hotel } => {
+                let cost = self.players[player_id]
+                    .deeds
+                    .iter()
+                    .map(|deed| match self.properties[*deed].buildings {
+                        HOTEL => hotel,
+                        houses => u32::from(houses) * house,
+                    })
+                    .sum();
+
+                outcomes.push(self.transfer(player_id, None, cost));
+            }
+            Effect::JailCard => {}
+            Effect::GoToJail => {
+                self.send_to_jail(player_id);
+                outcomes.push(Outcome::Jailed { player: player_id });
+            }
+        }
+
+        outcomes
+    }
+
+    /// Moves `player_id` forward to `target`, paying the salary if that
+    /// passes Go, and resolves the space with `rule`.
+    fn advance(&mut self, player_id: usize, target: usize, rule: RentRule) -> Vec<Outcome> {
+        let mut outcomes = vec![];
+
+        if target <= self.players[player_id].position {
+            let salary = self.salary(target);
+            self.players[player_id].cash += salary;
+            outcomes.push(Outcome::Salary {
+                player: player_id,
+                amount: salary,
+            });
+        }
+
+        self.players[player_id].position = target;
+        outcomes.push(Outcome::Move {
+            player: player_id,
+            position: target,
+        });
+        outcomes.append(&mut self.land(player_id, rule));
+
+        outcomes
+    }
+
+    fn nearest(&self, position: usize, matches: fn(&Space) -> bool) -> usize {
+        (1..BOARD_SIZE)
+            .map(|offset| (position + offset) % BOARD_SIZE)
+            .find(|position| matches(self.board.space(*position)))
+            .unwrap()
+    }
+
+    fn opponents(&self, player_id: usize) -> Vec<usize> {
+        self.players
+            .iter()
+            .filter(|player| player.id != player_id && !player.bankrupt)
+            .map(|player| player.id)
+            .collect()
+    }
+
+    fn deck_mut(&mut self, deck: Deck) -> &mut VecDeque<Card> {
+        match deck {
+            Deck::Chance => &mut self.chance,
+            Deck::CommunityChest => &mut self.community_chest,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::board::GO_SALARY;
+    use house_rules::STARTING_CASH;
+
+    const MEDITERRANEAN: usize = 1;
+    const BALTIC: usize = 3;
+    const INCOME_TAX: usize = 4;
+    const READING_RAILROAD: usize = 5;
+    const CHANCE_SPACE: usize = 7;
+
+    /// Has player 0 draw a Chance card with `effect` from the first Chance space.
+    fn draw(session: &mut Session, effect: Effect) -> Vec<Outcome> {
+        session.chance.push_front(Card { text: "", effect });
+        session.players[0].position = CHANCE_SPACE;
+
+        session.draw(0, Deck::Chance, 7)
+    }
+
+    #[test]
+    fn jail_cards_stay_with_the_player() {
+        let mut session = Session::playing(2);
+
+        draw(&mut session, Effect::JailCard);
+
+        assert_eq!(session.players[0].jail_cards, vec![Deck::Chance]);
+        assert_eq!(session.chance.len(), CHANCE.len());
+    }
+
+    #[test]
+    fn other_cards_go_to_the_bottom() {
+        let mut session = Session::playing(2);
+
+        draw(&mut session, Effect::Collect(50));
+
+        assert_eq!(session.chance.len(), CHANCE.len() + 1);
+        assert!(matches!(
+            session.chance.back().unwrap().effect,
+            Effect::Collect(50)
+        ));
+        assert_eq!(session.players[0].cash, STARTING_CASH + 50);
+    }
+
+    #[test]
+    fn going_back_resolves_the_new_space() {
+        let mut session = Session::playing(2);
+
+        draw(&mut session, Effect::Back(3));
+
+        assert_eq!(session.players[0].position, INCOME_TAX);
+        assert_eq!(session.players[0].cash, STARTING_CASH - 200);
+    }
+
+    #[test]
+    fn advancing_past_go_collects_the_salary() {
+        let mut session = Session::playing(2);
+
+        draw(&mut session, Effect::MoveTo(READING_RAILROAD));
+
+        assert_eq!(session.players[0].position, READING_RAILROAD);
+        assert_eq!(session.players[0].cash, STARTING_CASH + GO_SALARY);
+        assert_eq!(session.turn.pending, Some(READING_RAILROAD));
+    }
+
+    #[test]
+    fn repairs_are_charged_per_house_and_hotel() {
+        let mut session = Session::playing(2);
+        session.players[0].deeds.extend([MEDITERRANEAN, BALTIC]);
+        session.properties[MEDITERRANEAN].buildings = 2;
+        session.properties[BALTIC].buildings = HOTEL;
+
+        draw(
+            &mut session,
+            Effect::Repairs {
+                house: 25,
+                hotel: 100,
+            },
+        );
+
+        assert_eq!(session.players[0].cash, STARTING_CASH - 150);
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+
+/// Defines `GameError` along with its catalog, so a code, its name and its
+/// message can't drift apart.
+macro_rules! errors {
+    ($($variant:ident = $code:literal, $name:literal, $message:literal;)*) => {
+        /// Why a command was refused. Codes are part of the protocol and must
+        /// never be reused; names are stable too, messages may be reworded.
+        #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+        pub enum GameError {
+            $($variant = $code,)*
+        }
+
+        impl GameError {
+            /// Every error, in code order.
+            pub const ALL: &'static [GameError] = &[$(GameError::$variant,)*];
+
+            pub fn code(self) -> u8 {
+                self as u8
+            }
+
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(GameError::$variant => $name,)*
+                }
+            }
+
+            pub fn message(self) -> &'static str {
+                match self {
+                    $(GameError::$variant => $message,)*
+                }
+            }
+        }
+    };
+}
+
+errors! {
+    Malformed = 0, "MALFORMED", "The request could not be understood.";
+    MissingUsername = 1, "MISSING_USERNAME", "A username is required.";
+    UsernameTaken = 2, "USERNAME_TAKEN", "That username is already taken.";
+    MissingEcho = 3, "MISSING_ECHO", "There is nothing to echo.";
+    EchoConnect = 4, "ECHO_CONNECT", "The host server could not be reached.";
+    EchoWrite = 5, "ECHO_WRITE", "The echo could not be sent to the host server.";
+    EchoRead = 6, "ECHO_READ", "The host server's reply could not be read.";
+    HostTaken = 7, "HOST_TAKEN", "The game already has a host.";
+    InitState = 8, "INIT_STATE", "INIT or RESUME must come first, and only once.";
+    ChatTooLong = 9, "CHAT_TOO_LONG", "The message is too long.";
+    ChatEmpty = 10, "CHAT_EMPTY", "The message is empty.";
+    NotYourTurn = 11, "NOT_YOUR_TURN", "It is not your turn.";
+    AlreadyRolled = 12, "ALREADY_ROLLED", "You have already rolled this turn.";
+    NotRolled = 13, "NOT_ROLLED", "You must roll before ending your turn.";
+    NothingPending = 14, "NOTHING_PENDING", "There is no deed to buy or decline.";
+    InsufficientFunds = 15, "INSUFFICIENT_FUNDS", "You do not have enough cash.";
+    PurchasePending = 16, "PURCHASE_PENDING", "Buy or decline the deed you landed on first.";
+    AuctionInProgress = 17, "AUCTION_IN_PROGRESS", "An auction is in progress.";
+    NoAuction = 18, "NO_AUCTION", "There is no auction in progress.";
+    BidTooLow = 19, "BID_TOO_LOW", "The bid must beat the highest one.";
+    InvalidBid = 20, "INVALID_BID", "The bid amount is invalid.";
+    BidderBankrupt = 21, "BIDDER_BANKRUPT", "Bankrupt players cannot bid.";
+    UnknownTradePartner = 22, "UNKNOWN_TRADE_PARTNER", "There is no such player to trade with.";
+    InvalidTrade = 23, "INVALID_TRADE", "The trade is malformed or with yourself.";
+    UnknownTrade = 24, "UNKNOWN_TRADE", "There is no such trade for you.";
+    AssetsUnavailable = 25, "ASSETS_UNAVAILABLE", "Some of the traded assets are no longer available.";
+    InvalidPosition = 26, "INVALID_POSITION", "The board position is missing or invalid.";
+    NotOwned = 27, "NOT_OWNED", "You do not own a deed there that allows this.";
+    IncompleteSet = 28, "INCOMPLETE_SET", "You must own the whole color set.";
+    UnevenBuild = 29, "UNEVEN_BUILD", "Buildings must be spread evenly across the set.";
+    BankOutOfBuildings = 30, "BANK_OUT_OF_BUILDINGS", "The bank has no houses or hotels left.";
+    BuildingLimit = 31, "BUILDING_LIMIT", "There is nothing more to build or sell there.";
+    MortgageState = 32, "MORTGAGE_STATE", "The deed, or one in its set, is in the wrong mortgage state.";
+    GroupHasBuildings = 33, "GROUP_HAS_BUILDINGS", "Sell the buildings on the set first.";
+    MortgageDecisionPending = 34, "MORTGAGE_DECISION_PENDING", "Decide on the mortgaged deeds you received first.";
+    NoMortgageDecision = 35, "NO_MORTGAGE_DECISION", "There is no mortgage decision pending for that deed.";
+    NotInJail = 36, "NOT_IN_JAIL", "You are not in jail.";
+    NoJailCard = 37, "NO_JAIL_CARD", "You have no Get Out of Jail Free card.";
+    InDebt = 38, "IN_DEBT", "Pay your debts or declare bankruptcy first.";
+    NoDebt = 39, "NO_DEBT", "You have no debts.";
+    LobbyOpen = 40, "LOBBY_OPEN", "The game has not started yet.";
+    AlreadyStarted = 41, "ALREADY_STARTED", "The game has already started.";
+    NotHost = 42, "NOT_HOST", "Only the host can do that.";
+    NotReady = 43, "NOT_READY", "The game needs 2 to 8 players, all ready.";
+    LobbyFull = 44, "LOBBY_FULL", "The game is full.";
+    UnknownPlayer = 45, "UNKNOWN_PLAYER", "There is no such player.";
+    SelfTarget = 46, "SELF_TARGET", "You cannot target yourself.";
+    Kicked = 47, "KICKED", "You were removed from the game.";
+    Muted = 48, "MUTED", "You have been muted by the host.";
+    SeatConnected = 49, "SEAT_CONNECTED", "That seat is still connected.";
+    UnknownToken = 50, "UNKNOWN_TOKEN", "The resume token is unknown.";
+    SyncUnavailable = 51, "SYNC_UNAVAILABLE", "Events since that sequence number are no longer available.";
+    GameFinished = 52, "GAME_FINISHED", "The game is over.";
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.name(), self.code(), self.message())
+    }
+}
+
+impl std::error::Error for GameError {}
+
+pub type Result<T> = std::result::Result<T, GameError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_and_names_are_unique() {
+        assert!(GameError::ALL
+            .windows(2)
+            .all(|pair| pair[0].code() < pair[1].code()));
+
+        let mut names = GameError::ALL
+            .iter()
+            .map(|error| error.name())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), GameError::ALL.len());
+    }
+
+    #[test]
+    fn display_names_the_code() {
+        assert_eq!(
+            GameError::NotYourTurn.to_string(),
+            "NOT_YOUR_TURN (11): It is not your turn."
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use crate::game::error::{GameError, Result};
+use crate::game::turn::RollResult;
+use crate::game::{Outcome, Session};
+
+pub const BAIL: u32 = 50;
+
+/// Rolls allowed in jail before bail has to be paid.
+const MAX_JAIL_ROLLS: u8 = 3;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Release {
+    Bail,
+    Card,
+    Doubles,
+    /// Bail paid automatically after the third failed roll.
+    Forced,
+}
+
+impl Release {
+    pub fn name(self) -> &'static str {
+        match self {
+            Release::Bail => "BAIL",
+            Release::Card => "CARD",
+            Release::Doubles => "DOUBLES",
+            Release::Forced => "FORCED",
+        }
+    }
+}
+
+impl Session {
+    pub fn pay_bail(&mut self, player_id: usize) -> Result<Vec<Outcome>> {
+        self.check_release(player_id)?;
+
+        if self.players[player_id].cash < BAIL {
+            return Err(GameError::InsufficientFunds);
+        }
+
+        self.players[player_id].cash -= BAIL;
+        self.players[player_id].jailed = None;
+        self.bank_receives(BAIL);
+
+        Ok(vec![
+            Outcome::Transfer {
+                from: Some(player_id),
+                to: None,
+                amount: BAIL,
+            },
+            Outcome::Released {
+                player: player_id,
+                how: Release::Bail,
+            },
+        ])
+    }
+
+    pub fn use_jail_card(&mut self, player_id: usize) -> Result<Vec<Outcome>> {
+        self.check_release(player_id)?;
+
+        let Some(deck) = self.players[player_id].jail_cards.pop() else {
+            return Err(GameError::NoJailCard);
+        };
+
+        self.return_jail_card(deck);
+        self.players[player_id].jailed = None;
+
+        Ok(vec![Outcome::Released {
+            player: player_id,
+            how: Release::Card,
+        }])
+    }
+
+    /// Resolves a roll made from jail. Doubles free the player but do not
+    /// earn another roll; the third miss forces bail and moves them anyway.
+    pub(super) fn roll_in_jail(&mut self, player_id: usize, attempts: u8, result: &mut RollResult) {
+        self.turn.rolled = true;
+
+        let how = if result.is_doubles() {
+            Release::Doubles
+        } else if attempts + 1 == MAX_JAIL_ROLLS {
+            result.outcomes.push(self.transfer(player_id, None, BAIL));
+
+            Release::Forced
+        } else {
+            self.players[player_id].jailed = Some(attempts + 1);
+            return;
+        };
+
+        self.players[player_id].jailed = None;
+        result.outcomes.push(Outcome::Released {
+            player: player_id,
+            how,
+        });
+
+        self.move_by(player_id, result);
+    }
+
+    /// Bail and jail cards are only accepted from the active player before
+    /// they roll.
+    fn check_release(&self, player_id: usize) -> Result<()> {
+        if player_id != self.turn.player {
+            return Err(GameError::NotYourTurn);
+        }
+
+        if self.players[player_id].jailed.is_none() {
+            return Err(GameError::NotInJail);
+        }
+
+        if self.turn.rolled {
+            return Err(GameError::AlreadyRolled);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::board::JAIL_POSITION;
+    use crate::game::cards::Deck;
+    use house_rules::STARTING_CASH;
+
+    /// Player 0 in jail after `attempts` failed rolls.
+    fn jailed(attempts: u8) -> Session {
+        let mut session = Session::playing(2);
+        session.players[0].position = JAIL_POSITION;
+        session.players[0].jailed = Some(attempts);
+
+        session
+    }
+
+    fn roll(session: &mut Session, dice: [u8; 2]) -> RollResult {
+        let attempts = session.players[0].jailed.unwrap();
+        let mut result = RollResult {
+            dice,
+            position: JAIL_POSITION,
+            outcomes: vec![],
+        };
+        session.roll_in_jail(0, attempts, &mut result);
+
+        result
+    }
+
+    #[test]
+    fn missed_roll_stays_in_jail() {
+        let mut session = jailed(0);
+
+        let result = roll(&mut session, [2, 3]);
+
+        assert!(result.outcomes.is_empty());
+        assert_eq!(session.players[0].jailed, Some(1));
+        assert_eq!(session.players[0].position, JAIL_POSITION);
+        assert_eq!(session.players[0].cash, STARTING_CASH);
+    }
+
+    #[test]
+    fn doubles_release_without_bail() {
+        let mut session = jailed(1);
+
+        let result = roll(&mut session, [3, 3]);
+
+        assert!(matches!(
+            result.outcomes[0],
+            Outcome::Released {
+                player: 0,
+                how: Release::Doubles
+            }
+        ));
+        assert_eq!(session.players[0].jailed, None);
+        assert_eq!(session.players[0].position, JAIL_POSITION + 6);
+        assert_eq!(session.players[0].cash, STARTING_CASH);
+    }
+
+    #[test]
+    fn third_miss_forces_bail_and_moves() {
+        let mut session = jailed(MAX_JAIL_ROLLS - 1);
+
+        let result = roll(&mut session, [2, 3]);
+
+        assert!(matches!(
+            result.outcomes[..2],
+            [
+                Outcome::Transfer {
+                    from: Some(0),
+                    to: None,
+                    amount: BAIL
+                },
+                Outcome::Released {
+                    player: 0,
+                    how: Release::Forced
+                },
+            ]
+        ));
+        assert_eq!(session.players[0].jailed, None);
+        assert_eq!(session.players[0].position, JAIL_POSITION + 5);
+        assert_eq!(session.players[0].cash, STARTING_CASH - BAIL);
+    }
+
+    #[test]
+    fn bail_is_only_paid_before_rolling() {
+        let mut session = jailed(0);
+        session.turn.rolled = true;
+
+        assert_eq!(session.pay_bail(0).unwrap_err(), GameError::AlreadyRolled);
+
+        session.turn.rolled = false;
+        session.pay_bail(0).unwrap();
+        assert_eq!(session.players[0].jailed, None);
+        assert_eq!(session.players[0].cash, STARTING_CASH - BAIL);
+    }
+
+    #[test]
+    fn jail_card_goes_back_to_its_deck() {
+        let mut session = jailed(0);
+        assert_eq!(session.use_jail_card(0).unwrap_err(), GameError::NoJailCard);
+
+        let deck = session.community_chest.len();
+        session.players[0].jail_cards.push(Deck::CommunityChest);
+        session.use_jail_card(0).unwrap();
+
+        assert_eq!(session.players[0].jailed, None);
+        assert!(session.players[0].jail_cards.is_empty());
+        assert_eq!(session.community_chest.len(), deck + 1);
+    }
+}
@@ -0,0 +1,182 @@
+use rand::seq::SliceRandom;
+use serde::Serialize;
+
+use crate::game::error::{GameError, Result};
+use crate::game::turn::Turn;
+use crate::game::Session;
+
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = 8;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Lobby,
+    InProgress,
+    Finished,
+}
+
+impl Session {
+    pub fn started(&self) -> bool {
+        self.started
+    }
+
+    pub fn phase(&self) -> Phase {
+        if self.winner.is_some() {
+            Phase::Finished
+        } else if self.started {
+            Phase::InProgress
+        } else {
+            Phase::Lobby
+        }
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        self.winner
+    }
+
+    /// Players still seated, i.e. not kicked.
+    pub fn player_count(&self) -> usize {
+        self.players.len() - self.kicked.len()
+    }
+
+    /// Toggles whether `player_id` is ready to start, returning the new state.
+    pub fn toggle_ready(&mut self, player_id: usize) -> Result<bool> {
+        if self.started {
+            return Err(GameError::AlreadyStarted);
+        }
+
+        let player = &mut self.players[player_id];
+        player.ready = !player.ready;
+
+        Ok(player.ready)
+    }
+
+    /// Closes the lobby once every player is ready, dealing starting cash and
+    /// shuffling the turn order, which is returned.
+    pub fn start(&mut self, player_id: usize) -> Result<Vec<usize>> {
+        if self.started {
+            return Err(GameError::AlreadyStarted);
+        }
+
+        if !self.is_host(player_id) {
+            return Err(GameError::NotHost);
+        }
+
+        let seated = self
+            .players
+            .iter()
+            .filter(|player| !self.kicked.contains(&player.id))
+            .collect::<Vec<_>>();
+
+        if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&seated.len())
+            || seated.iter().any(|player| !player.ready)
+        {
+            return Err(GameError::NotReady);
+        }
+
+        self.order = seated.iter().map(|player| player.id).collect();
+
+        for id in &self.order {
+            self.players[*id].cash = self.rules.starting_cash;
+        }
+
+        self.order.shuffle(&mut self.rng);
+
+        self.turn = Turn::new(self.order[0]);
+        self.started = true;
+
+        Ok(self.order.clone())
+    }
+
+    pub fn is_host(&self, player_id: usize) -> bool {
+        self.host.as_deref() == Some(&*self.players[player_id].username)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use house_rules::STARTING_CASH;
+
+    #[test]
+    fn ready_toggles() {
+        let mut session = Session::lobby(2);
+
+        assert!(session.toggle_ready(1).unwrap());
+        assert!(!session.toggle_ready(1).unwrap());
+    }
+
+    #[test]
+    fn only_the_host_starts_once_everyone_is_ready() {
+        let mut session = Session::lobby(2);
+        session.toggle_ready(0).unwrap();
+        assert_eq!(session.start(0).unwrap_err(), GameError::NotReady);
+
+        session.toggle_ready(1).unwrap();
+        assert_eq!(session.start(1).unwrap_err(), GameError::NotHost);
+
+        let mut order = session.start(0).unwrap();
+        assert!(session.started());
+
+        order.sort_unstable();
+        assert_eq!(order, [0, 1]);
+        assert!(session
+            .players
+            .iter()
+            .all(|player| player.cash == STARTING_CASH));
+    }
+
+    #[test]
+    fn a_lone_player_cannot_start() {
+        let mut session = Session::lobby(1);
+        session.toggle_ready(0).unwrap();
+
+        assert_eq!(session.start(0).unwrap_err(), GameError::NotReady);
+    }
+
+    #[test]
+    fn lobby_closes_once_started() {
+        let mut session = Session::lobby(2);
+        session.toggle_ready(0).unwrap();
+        session.toggle_ready(1).unwrap();
+        session.start(0).unwrap();
+
+        assert_eq!(session.start(0).unwrap_err(), GameError::AlreadyStarted);
+        assert_eq!(
+            session.toggle_ready(1).unwrap_err(),
+            GameError::AlreadyStarted
+        );
+        assert_eq!(
+            session.add_player("late", None).unwrap_err(),
+            GameError::AlreadyStarted
+        );
+    }
+
+    #[test]
+    fn lobby_holds_at_most_eight() {
+        let mut session = Session::lobby(MAX_PLAYERS);
+
+        assert_eq!(
+            session.add_player("extra", None).unwrap_err(),
+            GameError::LobbyFull
+        );
+    }
+
+    #[test]
+    fn phase_follows_the_game() {
+        let mut session = Session::lobby(3);
+        assert_eq!(session.phase(), Phase::Lobby);
+
+        session.kick(0, "player2").unwrap();
+        assert_eq!(session.player_count(), 2);
+
+        session.toggle_ready(0).unwrap();
+        session.toggle_ready(1).unwrap();
+        session.start(0).unwrap();
+        assert_eq!(session.phase(), Phase::InProgress);
+
+        session.winner = Some(0);
+        assert_eq!(session.phase(), Phase::Finished);
+    }
+}
@@ -1,72 +1,425 @@
-use std::sync::Arc;
+use std::collections::VecDeque;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
 
 use async_std::os::unix::net::UnixStream;
-use eyre::{bail, Result};
 use parking_lot::Mutex;
+use rand::distributions::Alphanumeric;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 use soketto::Sender;
 
-#[derive(Debug, Clone)]
+use crate::api::back::Event;
+use crate::game::auction::{Auction, DEFAULT_AUCTION_TIMEOUT};
+use crate::game::bankruptcy::Debt;
+use crate::game::board::{Board, BOARD_SIZE};
+use crate::game::building::{HOTEL_SUPPLY, HOUSE_SUPPLY};
+use crate::game::cards::{Card, Deck};
+use crate::game::error::{GameError, Result};
+use crate::game::jail::Release;
+use crate::game::lobby::MAX_PLAYERS;
+use crate::game::property::PropertyState;
+use crate::game::rules::HouseRules;
+use crate::game::trade::Trade;
+use crate::game::turn::Turn;
+
+pub mod auction;
+pub mod bankruptcy;
+pub mod board;
+pub mod building;
+pub mod cards;
+pub mod error;
+pub mod jail;
+pub mod lobby;
+pub mod moderation;
+pub mod mortgage;
+pub mod property;
+pub mod rules;
+pub mod snapshot;
+pub mod trade;
+pub mod turn;
+
+const RESUME_TOKEN_LEN: usize = 32;
+
+/// Events kept per player for `SYNC` and while they are disconnected; older
+/// gaps need a `STATE` snapshot.
+const HISTORY_LEN: usize = 512;
+
+/// Connections and the events sent over them are not saved, so a restored
+/// player starts disconnected with nothing to `SYNC` from before the restore.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
     id: usize,
     username: String,
+    #[serde(skip)]
     sock: Option<Arc<Mutex<Sender<UnixStream>>>>,
+    /// Channel to the event handler of the player's connection.
+    #[serde(skip)]
+    events: Option<mpsc::Sender<(u64, Event)>>,
+    position: usize,
+    /// Failed attempts at rolling out of jail, or `None` when not in jail.
+    jailed: Option<u8>,
+    cash: u32,
+    deeds: Vec<usize>,
+    jail_cards: Vec<Deck>,
+    bankrupt: bool,
+    ready: bool,
+    /// Cleared when the connection drops so the player can take their seat back.
+    #[serde(skip)]
+    connected: bool,
+    /// Handed out on `INIT` for taking the seat back with `RESUME`.
+    resume_token: String,
+    /// Sequence number of the last event sent to the player.
+    seq: u64,
+    /// Recent events sent to the player, kept for `SYNC`.
+    #[serde(skip)]
+    history: VecDeque<(u64, Event)>,
+    /// Events held back while the player is disconnected, as many as the
+    /// history keeps. Anything older shows up as a gap in the sequence
+    /// numbers, calling for a `STATE` snapshot.
+    #[serde(skip)]
+    missed: VecDeque<(u64, Event)>,
+}
+
+impl Player {
+    pub fn sock(&self) -> Option<Arc<Mutex<Sender<UnixStream>>>> {
+        self.sock.clone()
+    }
 }
 
+/// A side effect of a game action that every player should hear about.
 #[derive(Debug, Clone)]
+pub enum Outcome {
+    Salary {
+        player: usize,
+        amount: u32,
+    },
+    Tax {
+        player: usize,
+        amount: u32,
+    },
+    Rent {
+        payer: usize,
+        owner: usize,
+        amount: u32,
+    },
+    Offer {
+        player: usize,
+        position: usize,
+    },
+    Jailed {
+        player: usize,
+    },
+    Released {
+        player: usize,
+        how: Release,
+    },
+    Card {
+        player: usize,
+        deck: Deck,
+        text: &'static str,
+    },
+    Move {
+        player: usize,
+        position: usize,
+    },
+    /// Cash changing hands, `None` standing for the bank.
+    Transfer {
+        from: Option<usize>,
+        to: Option<usize>,
+        amount: u32,
+    },
+    Debt {
+        debtor: usize,
+        creditor: Option<usize>,
+        amount: u32,
+    },
+    Bankrupt {
+        player: usize,
+        creditor: Option<usize>,
+    },
+    Turn {
+        player: usize,
+    },
+    GameOver {
+        winner: usize,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Message {
     user_id: usize,
     msg_id: usize,
     content: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Session {
     host: Option<String>,
     players: Vec<Player>,
     host_key: String,
     chat: Vec<Message>,
+    #[serde(skip, default = "Board::new")]
+    board: Board,
+    turn: Turn,
+    auction: Option<Auction>,
+    auction_queue: VecDeque<usize>,
+    auctions: usize,
+    auction_timeout: Duration,
+    trades: Vec<Trade>,
+    trade_count: usize,
+    properties: Vec<PropertyState>,
+    houses: u8,
+    hotels: u8,
+    /// Mortgaged deeds whose new owner has yet to pay the interest or lift the mortgage.
+    mortgage_decisions: Vec<(usize, usize)>,
+    chance: VecDeque<Card>,
+    community_chest: VecDeque<Card>,
+    debts: Vec<Debt>,
+    started: bool,
+    /// Player ids in turn order, shuffled when the game starts.
+    order: Vec<usize>,
+    kicked: Vec<usize>,
+    muted: Vec<usize>,
+    rules: HouseRules,
+    /// Free Parking pot, only filled when the house rule is on.
+    jackpot: u32,
+    turns: u32,
+    winner: Option<usize>,
+    /// When the last player left, or the session was created, while nobody
+    /// is connected.
+    #[serde(skip)]
+    idle_since: Option<Instant>,
+    /// Source of every roll, shuffle and token, reseeded before each command
+    /// so replaying the command log reproduces the game.
+    #[serde(skip, default = "StdRng::from_entropy")]
+    rng: StdRng,
 }
 
 impl Session {
     pub fn new() -> Session {
+        let mut rng = StdRng::from_entropy();
+
         Session {
             host: None,
             players: vec![],
             host_key: std::env::var("MONOPOLY_HOST_KEY").unwrap(),
             chat: vec![],
+            board: Board::new(),
+            turn: Turn::default(),
+            auction: None,
+            auction_queue: VecDeque::new(),
+            auctions: 0,
+            auction_timeout: std::env::var("MONOPOLY_AUCTION_TIMEOUT")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .map_or(DEFAULT_AUCTION_TIMEOUT, Duration::from_secs),
+            trades: vec![],
+            trade_count: 0,
+            properties: vec![PropertyState::default(); BOARD_SIZE],
+            houses: HOUSE_SUPPLY,
+            hotels: HOTEL_SUPPLY,
+            mortgage_decisions: vec![],
+            chance: cards::shuffled(Deck::Chance, &mut rng),
+            community_chest: cards::shuffled(Deck::CommunityChest, &mut rng),
+            debts: vec![],
+            started: false,
+            order: vec![],
+            kicked: vec![],
+            muted: vec![],
+            rules: HouseRules::default(),
+            jackpot: 0,
+            turns: 0,
+            winner: None,
+            idle_since: Some(Instant::now()),
+            rng,
         }
     }
 
-    pub fn add_player(&mut self, username: &String, host_key: &Option<String>) -> Result<usize> {
-        for player in &self.players {
-            if &player.username == username {
-                bail!("2");
-            }
+    /// Seats a new player in the lobby.
+    pub fn add_player(&mut self, username: &str, host_key: Option<&String>) -> Result<usize> {
+        if self.player_id_by_username(username).is_some() {
+            return Err(GameError::UsernameTaken);
+        }
+
+        if self.started {
+            return Err(GameError::AlreadyStarted);
+        }
+
+        if self.player_count() == MAX_PLAYERS {
+            return Err(GameError::LobbyFull);
         }
 
         if let Some(key) = host_key {
             if self.host.is_some() {
-                bail!("7");
+                return Err(GameError::HostTaken);
             }
 
             if &*self.host_key == key {
-                self.host = Some(username.clone())
+                self.host = Some(username.to_string());
             }
         }
 
         self.players.push(Player {
             id: self.players.len(),
-            username: username.clone(),
+            username: username.to_string(),
             sock: None,
+            events: None,
+            position: 0,
+            jailed: None,
+            cash: 0,
+            deeds: vec![],
+            jail_cards: vec![],
+            bankrupt: false,
+            ready: false,
+            connected: true,
+            resume_token: (&mut self.rng)
+                .sample_iter(Alphanumeric)
+                .take(RESUME_TOKEN_LEN)
+                .map(char::from)
+                .collect(),
+            seq: 0,
+            history: VecDeque::new(),
+            missed: VecDeque::new(),
         });
 
         Ok(self.players.len() - 1)
     }
 
+    /// Hands a disconnected player their seat back.
+    pub fn resume(&mut self, token: &str) -> Result<usize> {
+        let Some(id) = self
+            .players
+            .iter()
+            .position(|player| player.resume_token == token)
+        else {
+            return Err(GameError::UnknownToken);
+        };
+
+        if self.kicked.contains(&id) {
+            return Err(GameError::Kicked);
+        }
+
+        if self.players[id].connected {
+            return Err(GameError::SeatConnected);
+        }
+
+        self.players[id].connected = true;
+
+        Ok(id)
+    }
+
+    pub fn resume_token(&self, id: usize) -> &str {
+        &self.players[id].resume_token
+    }
+
     pub fn assoc_sock(&mut self, id: usize, send: Arc<Mutex<Sender<UnixStream>>>) {
         self.players[id].sock = Some(send);
     }
 
+    /// Routes events meant for player `id` to the handler behind `events`,
+    /// starting with whatever they missed while disconnected.
+    pub fn subscribe(&mut self, id: usize, events: mpsc::Sender<(u64, Event)>) {
+        for event in std::mem::take(&mut self.players[id].missed) {
+            events.send(event).ok();
+        }
+
+        self.players[id].events = Some(events);
+        self.idle_since = None;
+    }
+
+    /// Sends `event` to every player still in the session.
+    pub fn publish(&mut self, event: &Event) {
+        for id in 0..self.players.len() {
+            if !self.kicked.contains(&id) {
+                self.deliver(id, event);
+            }
+        }
+    }
+
+    /// Numbers `event` and sends it to player `id`, holding it back while
+    /// they are disconnected.
+    pub fn deliver(&mut self, id: usize, event: &Event) {
+        let player = &mut self.players[id];
+        player.seq += 1;
+
+        player.history.push_back((player.seq, event.clone()));
+        if player.history.len() > HISTORY_LEN {
+            player.history.pop_front();
+        }
+
+        let sent = player
+            .events
+            .as_ref()
+            .is_some_and(|events| events.send((player.seq, event.clone())).is_ok());
+
+        if !sent {
+            player.missed.push_back((player.seq, event.clone()));
+            if player.missed.len() > HISTORY_LEN {
+                player.missed.pop_front();
+            }
+        }
+    }
+
+    /// Events sent to player `id` after `since`, for a client that noticed a
+    /// gap in the sequence numbers.
+    pub fn sync(&self, id: usize, since: u64) -> Result<Vec<(u64, Event)>> {
+        let player = &self.players[id];
+        let oldest = player
+            .history
+            .front()
+            .map_or(player.seq + 1, |(seq, _)| *seq);
+
+        if since > player.seq || since + 1 < oldest {
+            return Err(GameError::SyncUnavailable);
+        }
+
+        Ok(player
+            .history
+            .iter()
+            .filter(|(seq, _)| *seq > since)
+            .cloned()
+            .collect())
+    }
+
+    /// Frees the seat of `id` after their connection drops.
+    pub fn disconnect(&mut self, id: usize) {
+        let player = &mut self.players[id];
+        player.sock = None;
+        player.events = None;
+        player.connected = false;
+
+        if self.players.iter().all(|player| !player.connected) {
+            self.idle_since = Some(Instant::now());
+        }
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Brings a session read back from disk to life: nobody is connected yet,
+    /// including players whose `INIT` was replayed from the log.
+    pub fn restored(&mut self) {
+        for player in &mut self.players {
+            player.sock = None;
+            player.events = None;
+            player.connected = false;
+        }
+
+        self.idle_since = Some(Instant::now());
+    }
+
+    /// How long nobody has been connected, if that is the case.
+    pub fn idle_for(&self) -> Option<Duration> {
+        self.idle_since.map(|since| since.elapsed())
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
     pub fn players(&mut self) -> &Vec<Player> {
         &mut self.players
     }
@@ -109,3 +462,144 @@ impl Default for Session {
         Self::new()
     }
 }
+
+#[cfg(test)]
+impl Session {
+    /// A lobby of `count` players hosted by player 0, none of them ready.
+    fn lobby(count: usize) -> Session {
+        std::env::set_var("MONOPOLY_HOST_KEY", "host");
+
+        let mut session = Session::new();
+        let host_key = session.host_key.clone();
+
+        for n in 0..count {
+            session
+                .add_player(&format!("player{n}"), (n == 0).then_some(&host_key))
+                .unwrap();
+        }
+
+        session
+    }
+
+    /// A game of `count` players under way, with player 0 to move first.
+    fn playing(count: usize) -> Session {
+        let mut session = Session::lobby(count);
+
+        for id in 0..count {
+            session.toggle_ready(id).unwrap();
+        }
+        session.start(0).unwrap();
+
+        session.order = (0..count).collect();
+        session.turn = Turn::new(0);
+
+        session
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::back::Message;
+
+    #[test]
+    fn resume_takes_back_a_dropped_seat() {
+        let mut session = Session::lobby(2);
+        let token = session.resume_token(1).to_string();
+
+        assert_eq!(
+            session.resume(&token).unwrap_err(),
+            GameError::SeatConnected
+        );
+
+        session.disconnect(1);
+        assert_eq!(session.resume(&token).unwrap(), 1);
+        assert_eq!(
+            session.resume("unknown").unwrap_err(),
+            GameError::UnknownToken
+        );
+    }
+
+    #[test]
+    fn kicked_players_cannot_resume() {
+        let mut session = Session::lobby(2);
+        let token = session.resume_token(1).to_string();
+
+        session.kick(0, "player1").unwrap();
+        session.disconnect(1);
+
+        assert_eq!(session.resume(&token).unwrap_err(), GameError::Kicked);
+    }
+
+    #[test]
+    fn missed_events_arrive_once_resubscribed() {
+        let mut session = Session::lobby(2);
+        session.disconnect(1);
+        session.publish(&Event::Msg(Message::new("player0", "hi")));
+
+        let (events, received) = mpsc::channel();
+        session.subscribe(1, events);
+
+        assert!(matches!(received.try_recv(), Ok((1, Event::Msg(_)))));
+        assert!(received.try_recv().is_err());
+    }
+
+    fn chat(session: &mut Session) {
+        session.publish(&Event::Msg(Message::new("player0", "hi")));
+    }
+
+    #[test]
+    fn events_are_numbered_per_player() {
+        let mut session = Session::lobby(2);
+        session.disconnect(1);
+        chat(&mut session);
+        chat(&mut session);
+
+        let (events, received) = mpsc::channel();
+        session.subscribe(1, events);
+
+        let seqs = received.try_iter().map(|(seq, _)| seq).collect::<Vec<_>>();
+        assert_eq!(seqs, [1, 2]);
+    }
+
+    #[test]
+    fn sync_replays_events_after_a_gap() {
+        let mut session = Session::lobby(2);
+        for _ in 0..3 {
+            chat(&mut session);
+        }
+
+        let seqs =
+            |events: Vec<(u64, Event)>| events.iter().map(|(seq, _)| *seq).collect::<Vec<_>>();
+        assert_eq!(seqs(session.sync(1, 1).unwrap()), [2, 3]);
+        assert!(session.sync(1, 3).unwrap().is_empty());
+        assert_eq!(session.sync(1, 4).unwrap_err(), GameError::SyncUnavailable);
+    }
+
+    #[test]
+    fn sync_needs_the_gap_to_be_in_history() {
+        let mut session = Session::lobby(2);
+        for _ in 0..=HISTORY_LEN {
+            chat(&mut session);
+        }
+
+        assert_eq!(session.sync(1, 0).unwrap_err(), GameError::SyncUnavailable);
+        assert_eq!(session.sync(1, 1).unwrap().len(), HISTORY_LEN);
+    }
+
+    #[test]
+    fn idle_until_someone_subscribes() {
+        let mut session = Session::lobby(2);
+        assert!(session.idle_for().is_some());
+
+        let (events, _received) = mpsc::channel();
+        session.subscribe(0, events);
+        assert!(session.idle_for().is_none());
+
+        session.disconnect(1);
+        assert!(session.idle_for().is_none());
+
+        session.disconnect(0);
+        assert!(session.idle_for().is_some());
+    }
+}
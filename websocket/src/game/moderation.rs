@@ -0,0 +1,140 @@
+use crate::game::error::{GameError, Result};
+use crate::game::{Outcome, Session};
+
+impl Session {
+    /// Removes `target` from the game on behalf of the host. Their assets go
+    /// to the bank, which may start an auction whose id is returned along with
+    /// the target's id.
+    pub fn kick(
+        &mut self,
+        player_id: usize,
+        target: &str,
+    ) -> Result<(usize, Vec<Outcome>, Option<usize>)> {
+        let target = self.moderated(player_id, target)?;
+
+        self.kicked.push(target);
+        self.debts.retain(|debt| debt.debtor != target);
+
+        let (outcomes, auction) = self.retire(target, None);
+
+        Ok((target, outcomes, auction))
+    }
+
+    /// Toggles whether `target` may chat, returning whether they are now muted.
+    pub fn mute(&mut self, player_id: usize, target: &str) -> Result<bool> {
+        let target = self.moderated(player_id, target)?;
+
+        let muted = !self.is_muted(target);
+        if muted {
+            self.muted.push(target);
+        } else {
+            self.muted.retain(|player| *player != target);
+        }
+
+        Ok(muted)
+    }
+
+    pub fn transfer_host(&mut self, player_id: usize, target: &str) -> Result<()> {
+        let target = self.moderated(player_id, target)?;
+
+        self.host = Some(self.players[target].username.clone());
+
+        Ok(())
+    }
+
+    pub fn is_muted(&self, player_id: usize) -> bool {
+        self.muted.contains(&player_id)
+    }
+
+    /// Only the host may moderate, and only other players still in the session.
+    fn moderated(&self, player_id: usize, target: &str) -> Result<usize> {
+        if !self.is_host(player_id) {
+            return Err(GameError::NotHost);
+        }
+
+        let Some(target) = self.player_id_by_username(target) else {
+            return Err(GameError::UnknownPlayer);
+        };
+
+        if self.kicked.contains(&target) {
+            return Err(GameError::UnknownPlayer);
+        }
+
+        if target == player_id {
+            return Err(GameError::SelfTarget);
+        }
+
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEDITERRANEAN: usize = 1;
+
+    #[test]
+    fn only_the_host_moderates_others() {
+        let mut session = Session::lobby(2);
+
+        assert_eq!(session.mute(1, "player0").unwrap_err(), GameError::NotHost);
+        assert_eq!(
+            session.mute(0, "player0").unwrap_err(),
+            GameError::SelfTarget
+        );
+        assert_eq!(
+            session.mute(0, "nobody").unwrap_err(),
+            GameError::UnknownPlayer
+        );
+    }
+
+    #[test]
+    fn mute_toggles() {
+        let mut session = Session::lobby(2);
+
+        assert!(session.mute(0, "player1").unwrap());
+        assert!(session.is_muted(1));
+        assert!(!session.mute(0, "player1").unwrap());
+        assert!(!session.is_muted(1));
+    }
+
+    #[test]
+    fn host_can_be_handed_over() {
+        let mut session = Session::lobby(2);
+
+        session.transfer_host(0, "player1").unwrap();
+
+        assert!(session.is_host(1));
+        assert!(!session.is_host(0));
+    }
+
+    #[test]
+    fn kicked_players_lose_their_seat_and_deeds() {
+        let mut session = Session::playing(3);
+        session.players[1].deeds.push(MEDITERRANEAN);
+
+        let (target, _, auction) = session.kick(0, "player1").unwrap();
+
+        assert_eq!(target, 1);
+        assert!(auction.is_some());
+        assert_eq!(session.owner_of(MEDITERRANEAN), None);
+        assert_eq!(
+            session.kick(0, "player1").unwrap_err(),
+            GameError::UnknownPlayer
+        );
+    }
+
+    #[test]
+    fn kicked_players_do_not_hold_up_the_lobby() {
+        let mut session = Session::lobby(3);
+        session.toggle_ready(0).unwrap();
+        session.toggle_ready(2).unwrap();
+
+        session.kick(0, "player1").unwrap();
+
+        let mut order = session.start(0).unwrap();
+        order.sort_unstable();
+        assert_eq!(order, [0, 2]);
+    }
+}
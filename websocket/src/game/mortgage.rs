@@ -0,0 +1,177 @@
+use crate::game::error::{GameError, Result};
+use crate::game::Session;
+
+impl Session {
+    /// Mortgages a deed, returning the amount paid out by the bank.
+    pub fn mortgage(&mut self, player_id: usize, position: usize) -> Result<u32> {
+        let value = self.mortgageable(player_id, position)?;
+
+        if self.properties[position].mortgaged {
+            return Err(GameError::MortgageState);
+        }
+
+        if self.group_has_buildings(position) {
+            return Err(GameError::GroupHasBuildings);
+        }
+
+        self.properties[position].mortgaged = true;
+        self.players[player_id].cash += value;
+
+        Ok(value)
+    }
+
+    /// Lifts a mortgage for its value plus interest, returning the amount
+    /// paid. Also settles a pending decision on a deed that just changed hands.
+    pub fn unmortgage(&mut self, player_id: usize, position: usize) -> Result<u32> {
+        let value = self.mortgageable(player_id, position)?;
+
+        if !self.properties[position].mortgaged {
+            return Err(GameError::MortgageState);
+        }
+
+        let cost = value + Session::interest(value);
+        if self.players[player_id].cash < cost {
+            return Err(GameError::InsufficientFunds);
+        }
+
+        self.players[player_id].cash -= cost;
+        self.properties[position].mortgaged = false;
+        self.mortgage_decisions
+            .retain(|decision| *decision != (player_id, position));
+
+        Ok(cost)
+    }
+
+    /// Pays only the interest on a mortgaged deed that just changed hands,
+    /// leaving it mortgaged. Returns the interest paid.
+    pub fn keep_mortgage(&mut self, player_id: usize, position: usize) -> Result<u32> {
+        let value = self.mortgageable(player_id, position)?;
+
+        if !self.mortgage_decisions.contains(&(player_id, position)) {
+            return Err(GameError::NoMortgageDecision);
+        }
+
+        let interest = Session::interest(value);
+        if self.players[player_id].cash < interest {
+            return Err(GameError::InsufficientFunds);
+        }
+
+        self.players[player_id].cash -= interest;
+        self.mortgage_decisions
+            .retain(|decision| *decision != (player_id, position));
+
+        Ok(interest)
+    }
+
+    /// Mortgaged deeds `player_id` received that still need a decision.
+    pub fn mortgage_decisions(&self, player_id: usize) -> Vec<usize> {
+        self.mortgage_decisions
+            .iter()
+            .filter(|(owner, _)| *owner == player_id)
+            .map(|(_, position)| *position)
+            .collect()
+    }
+
+    pub(super) fn has_mortgage_decisions(&self, player_id: usize) -> bool {
+        self.mortgage_decisions
+            .iter()
+            .any(|(owner, _)| *owner == player_id)
+    }
+
+    /// Gives `position` to `player_id`, who must then decide what to do
+    /// about the mortgage if the deed is mortgaged.
+    pub(super) fn receive_deed(&mut self, player_id: usize, position: usize) {
+        self.players[player_id].deeds.push(position);
+        self.mortgage_decisions
+            .retain(|(_, decided)| *decided != position);
+
+        if self.properties[position].mortgaged {
+            self.mortgage_decisions.push((player_id, position));
+        }
+    }
+
+    /// 10% interest on a mortgage value, rounded up.
+    pub fn interest(value: u32) -> u32 {
+        value.div_ceil(10)
+    }
+
+    fn mortgageable(&self, player_id: usize, position: usize) -> Result<u32> {
+        let Some(value) = self.board.space(position).mortgage_value() else {
+            return Err(GameError::NotOwned);
+        };
+
+        if self.owner_of(position) != Some(player_id) {
+            return Err(GameError::NotOwned);
+        }
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use house_rules::STARTING_CASH;
+
+    const MEDITERRANEAN: usize = 1;
+    const BALTIC: usize = 3;
+    const ELECTRIC_COMPANY: usize = 12;
+    const PARK_PLACE: usize = 37;
+
+    #[test]
+    fn interest_rounds_up() {
+        assert_eq!(Session::interest(30), 3);
+        assert_eq!(Session::interest(75), 8);
+        assert_eq!(Session::interest(175), 18);
+    }
+
+    #[test]
+    fn unmortgaging_costs_value_plus_interest() {
+        let mut session = Session::playing(2);
+        session.players[0]
+            .deeds
+            .extend([MEDITERRANEAN, ELECTRIC_COMPANY, PARK_PLACE]);
+
+        assert_eq!(session.mortgage(0, ELECTRIC_COMPANY).unwrap(), 75);
+        assert_eq!(session.unmortgage(0, ELECTRIC_COMPANY).unwrap(), 83);
+
+        assert_eq!(session.mortgage(0, PARK_PLACE).unwrap(), 175);
+        assert_eq!(session.unmortgage(0, PARK_PLACE).unwrap(), 193);
+
+        assert_eq!(session.players[0].cash, STARTING_CASH - 8 - 18);
+        assert_eq!(
+            session.unmortgage(0, MEDITERRANEAN).unwrap_err(),
+            GameError::MortgageState
+        );
+    }
+
+    #[test]
+    fn received_mortgage_can_be_kept_for_interest() {
+        let mut session = Session::playing(2);
+        session.players[1].deeds.push(ELECTRIC_COMPANY);
+        session.mortgage(1, ELECTRIC_COMPANY).unwrap();
+
+        session.players[1].deeds.clear();
+        session.receive_deed(0, ELECTRIC_COMPANY);
+        assert_eq!(session.mortgage_decisions(0), vec![ELECTRIC_COMPANY]);
+
+        assert_eq!(session.keep_mortgage(0, ELECTRIC_COMPANY).unwrap(), 8);
+        assert!(session.properties[ELECTRIC_COMPANY].mortgaged);
+        assert_eq!(
+            session.keep_mortgage(0, ELECTRIC_COMPANY).unwrap_err(),
+            GameError::NoMortgageDecision
+        );
+    }
+
+    #[test]
+    fn streets_with_buildings_in_the_group_cannot_be_mortgaged() {
+        let mut session = Session::playing(2);
+        session.players[0].deeds.extend([MEDITERRANEAN, BALTIC]);
+        session.properties[BALTIC].buildings = 1;
+
+        assert_eq!(
+            session.mortgage(0, MEDITERRANEAN).unwrap_err(),
+            GameError::GroupHasBuildings
+        );
+    }
+}
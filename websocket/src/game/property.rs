@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::bankruptcy::Debt;
+use crate::game::board::{ColorGroup, Railroad, Space, Utility};
+use crate::game::cards::Deck;
+use crate::game::error::{GameError, Result};
+use crate::game::{Outcome, Session};
+
+/// How rent is worked out for the space a player lands on.
+#[derive(Debug, Clone, Copy)]
+pub enum RentRule {
+    /// Regular rent, `dice` being the total of the roll that got them there.
+    Standard { dice: usize },
+    /// Twice the regular railroad rent.
+    DoubleRailroad,
+    /// Ten times `dice` for a utility, regardless of how many are owned.
+    TenTimesDice { dice: usize },
+}
+
+/// Mutable state of a deed, indexed by board position.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PropertyState {
+    /// Houses on the street, with `HOTEL` standing for a hotel.
+    pub buildings: u8,
+    pub mortgaged: bool,
+}
+
+impl Session {
+    /// Id of the player holding the deed at `position`, if anyone does.
+    pub fn owner_of(&self, position: usize) -> Option<usize> {
+        self.players
+            .iter()
+            .find(|player| player.deeds.contains(&position))
+            .map(|player| player.id)
+    }
+
+    pub fn buy(&mut self, player_id: usize) -> Result<(usize, u32)> {
+        if player_id != self.turn.player {
+            return Err(GameError::NotYourTurn);
+        }
+
+        let Some(position) = self.turn.pending else {
+            return Err(GameError::NothingPending);
+        };
+
+        let price = self.board.space(position).price().unwrap();
+        let player = &mut self.players[player_id];
+
+        if player.cash < price {
+            return Err(GameError::InsufficientFunds);
+        }
+
+        player.cash -= price;
+        player.deeds.push(position);
+        self.turn.pending = None;
+
+        Ok((position, price))
+    }
+
+    /// Turns down the deed on offer and puts it up for auction, returning
+    /// its position and the id of the auction.
+    pub fn decline(&mut self, player_id: usize) -> Result<(usize, Option<usize>)> {
+        if player_id != self.turn.player {
+            return Err(GameError::NotYourTurn);
+        }
+
+        let Some(position) = self.turn.pending.take() else {
+            return Err(GameError::NothingPending);
+        };
+
+        if !self.rules.auctions {
+            return Ok((position, None));
+        }
+
+        Ok((position, Some(self.start_auction(position))))
+    }
+
+    /// Resolves the space `player_id` just moved onto.
+    pub(super) fn land(&mut self, player_id: usize, rule: RentRule) -> Vec<Outcome> {
+        let position = self.players[player_id].position;
+
+        match self.board.space(position) {
+            Space::Chance => self.draw(player_id, Deck::Chance, rule.dice()),
+            Space::CommunityChest => self.draw(player_id, Deck::CommunityChest, rule.dice()),
+            Space::GoToJail => {
+                self.send_to_jail(player_id);
+
+                vec![Outcome::Jailed { player: player_id }]
+            }
+            Space::FreeParking if self.jackpot > 0 => {
+                let amount = std::mem::take(&mut self.jackpot);
+                self.players[player_id].cash += amount;
+
+                vec![Outcome::Transfer {
+                    from: None,
+                    to: Some(player_id),
+                    amount,
+                }]
+            }
+            Space::Tax { amount, .. } => {
+                let amount = *amount;
+
+                vec![self.pay(player_id, None, amount).unwrap_or(Outcome::Tax {
+                    player: player_id,
+                    amount,
+                })]
+            }
+            space if space.is_property() => match self.owner_of(position) {
+                None => {
+                    self.turn.pending = Some(position);
+
+                    vec![Outcome::Offer {
+                        player: player_id,
+                        position,
+                    }]
+                }
+                Some(owner) if owner != player_id && !self.properties[position].mortgaged => {
+                    let rent = match rule {
+                        RentRule::Standard { dice } => self.rent(position, dice),
+                        RentRule::DoubleRailroad => self.rent(position, 0) * 2,
+                        RentRule::TenTimesDice { dice } => 10 * u32::try_from(dice).unwrap(),
+                    };
+                    vec![self
+                        .pay(player_id, Some(owner), rent)
+                        .unwrap_or(Outcome::Rent {
+                            payer: player_id,
+                            owner,
+                            amount: rent,
+                        })]
+                }
+                Some(_) => vec![],
+            },
+            _ => vec![],
+        }
+    }
+
+    /// Rent owed for landing on the owned property at `position`.
+    pub fn rent(&self, position: usize, dice: usize) -> u32 {
+        let Some(owner) = self.owner_of(position) else {
+            return 0;
+        };
+
+        let deed_count = |matches: fn(&Space) -> bool| {
+            self.players[owner]
+                .deeds
+                .iter()
+                .filter(|deed| matches(self.board.space(**deed)))
+                .count()
+        };
+
+        match self.board.space(position) {
+            Space::Street(street) => {
+                let buildings = self.properties[position].buildings;
+
+                if buildings > 0 {
+                    street.rent[usize::from(buildings)]
+                } else if self.owns_group(owner, street.group) {
+                    street.rent[0] * 2
+                } else {
+                    street.rent[0]
+                }
+            }
+            Space::Railroad(_) => {
+                Railroad::rent(deed_count(|space| matches!(space, Space::Railroad(_))))
+            }
+            Space::Utility(_) => {
+                let multiplier =
+                    Utility::multiplier(deed_count(|space| matches!(space, Space::Utility(_))));
+
+                multiplier * u32::try_from(dice).unwrap()
+            }
+            _ => 0,
+        }
+    }
+
+    pub(super) fn owns_group(&self, player_id: usize, group: ColorGroup) -> bool {
+        self.board
+            .group_positions(group)
+            .iter()
+            .all(|position| self.players[player_id].deeds.contains(position))
+    }
+
+    /// Moves `amount` from `payer` to `creditor`, or to the bank when
+    /// `creditor` is `None`. A payer who cannot cover it is left owing the
+    /// whole amount, which is returned as a debt outcome.
+    pub(super) fn pay(
+        &mut self,
+        payer: usize,
+        creditor: Option<usize>,
+        amount: u32,
+    ) -> Option<Outcome> {
+        if self.players[payer].cash < amount {
+            self.debts.push(Debt {
+                debtor: payer,
+                creditor,
+                amount,
+            });
+
+            return Some(Outcome::Debt {
+                debtor: payer,
+                creditor,
+                amount,
+            });
+        }
+
+        self.players[payer].cash -= amount;
+        match creditor {
+            Some(creditor) => self.players[creditor].cash += amount,
+            None => self.bank_receives(amount),
+        }
+
+        None
+    }
+
+    /// Like [`Session::pay`], reporting a settled payment as a transfer.
+    pub(super) fn transfer(&mut self, from: usize, to: Option<usize>, amount: u32) -> Outcome {
+        self.pay(from, to, amount).unwrap_or(Outcome::Transfer {
+            from: Some(from),
+            to,
+            amount,
+        })
+    }
+}
+
+impl RentRule {
+    fn dice(self) -> usize {
+        match self {
+            RentRule::Standard { dice } | RentRule::TenTimesDice { dice } => dice,
+            RentRule::DoubleRailroad => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use house_rules::STARTING_CASH;
+
+    const MEDITERRANEAN: usize = 1;
+    const BALTIC: usize = 3;
+    const RAILROADS: [usize; 4] = [5, 15, 25, 35];
+    const ELECTRIC_COMPANY: usize = 12;
+    const WATER_WORKS: usize = 28;
+
+    #[test]
+    fn full_set_doubles_unimproved_rent() {
+        let mut session = Session::playing(2);
+
+        session.players[1].deeds.push(MEDITERRANEAN);
+        assert_eq!(session.rent(MEDITERRANEAN, 7), 2);
+
+        session.players[1].deeds.push(BALTIC);
+        assert_eq!(session.rent(MEDITERRANEAN, 7), 4);
+        assert_eq!(session.rent(BALTIC, 7), 8);
+
+        session.properties[MEDITERRANEAN].buildings = 1;
+        assert_eq!(session.rent(MEDITERRANEAN, 7), 10);
+    }
+
+    #[test]
+    fn railroad_rent_follows_railroads_owned() {
+        let mut session = Session::playing(2);
+
+        for (railroad, rent) in RAILROADS.into_iter().zip([25, 50, 100, 200]) {
+            session.players[1].deeds.push(railroad);
+            assert_eq!(session.rent(RAILROADS[0], 0), rent);
+        }
+    }
+
+    #[test]
+    fn utility_rent_multiplies_dice() {
+        let mut session = Session::playing(2);
+
+        session.players[1].deeds.push(ELECTRIC_COMPANY);
+        assert_eq!(session.rent(ELECTRIC_COMPANY, 7), 28);
+
+        session.players[1].deeds.push(WATER_WORKS);
+        assert_eq!(session.rent(ELECTRIC_COMPANY, 7), 70);
+    }
+
+    #[test]
+    fn unowned_property_has_no_rent() {
+        let session = Session::playing(2);
+
+        assert_eq!(session.rent(MEDITERRANEAN, 7), 0);
+    }
+
+    #[test]
+    fn buying_needs_an_offer_and_the_cash() {
+        let mut session = Session::playing(2);
+        assert_eq!(session.buy(0).unwrap_err(), GameError::NothingPending);
+
+        session.turn.pending = Some(MEDITERRANEAN);
+        session.players[0].cash = 50;
+        assert_eq!(session.buy(0).unwrap_err(), GameError::InsufficientFunds);
+
+        session.players[0].cash = STARTING_CASH;
+        assert_eq!(session.buy(0).unwrap(), (MEDITERRANEAN, 60));
+        assert_eq!(session.players[0].cash, STARTING_CASH - 60);
+        assert_eq!(session.owner_of(MEDITERRANEAN), Some(0));
+    }
+}
@@ -0,0 +1,154 @@
+pub use house_rules::HouseRules;
+
+use crate::game::board::{Space, GO_SALARY};
+use crate::game::error::{GameError, Result};
+use crate::game::Session;
+
+impl Session {
+    pub fn rules(&self) -> &HouseRules {
+        &self.rules
+    }
+
+    /// Replaces the house rules, which can only change before the game starts.
+    pub fn set_rules(&mut self, rules: HouseRules) -> Result<()> {
+        if self.started {
+            return Err(GameError::AlreadyStarted);
+        }
+
+        self.rules = rules;
+
+        Ok(())
+    }
+
+    /// Salary for passing Go, ending up at `position`.
+    pub(super) fn salary(&self, position: usize) -> u32 {
+        if self.rules.double_go_salary && position == 0 {
+            GO_SALARY * 2
+        } else {
+            GO_SALARY
+        }
+    }
+
+    /// Cash, deeds at their price (or mortgage value) and buildings at cost.
+    pub fn net_worth(&self, player_id: usize) -> u32 {
+        let player = &self.players[player_id];
+
+        player.cash
+            + player
+                .deeds
+                .iter()
+                .map(|deed| {
+                    let space = self.board.space(*deed);
+                    let value = if self.properties[*deed].mortgaged {
+                        space.mortgage_value()
+                    } else {
+                        space.price()
+                    };
+
+                    let buildings = match space {
+                        Space::Street(street) => {
+                            u32::from(self.properties[*deed].buildings) * street.house_cost
+                        }
+                        _ => 0,
+                    };
+
+                    value.unwrap_or(0) + buildings
+                })
+                .sum::<u32>()
+    }
+
+    /// Puts money paid to the bank in the Free Parking pot, if that rule is on.
+    pub(super) fn bank_receives(&mut self, amount: u32) {
+        if self.rules.free_parking_jackpot {
+            self.jackpot += amount;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::property::RentRule;
+    use crate::game::Outcome;
+    use house_rules::STARTING_CASH;
+
+    const INCOME_TAX: usize = 4;
+    const MEDITERRANEAN: usize = 1;
+    const FREE_PARKING: usize = 20;
+
+    #[test]
+    fn free_parking_collects_what_the_bank_was_paid() {
+        let mut session = Session::playing(2);
+        session.rules.free_parking_jackpot = true;
+
+        session.players[0].position = INCOME_TAX;
+        session.land(0, RentRule::Standard { dice: 4 });
+        assert_eq!(session.jackpot, 200);
+
+        session.players[1].position = FREE_PARKING;
+        session.land(1, RentRule::Standard { dice: 4 });
+        assert_eq!(session.jackpot, 0);
+        assert_eq!(session.players[1].cash, STARTING_CASH + 200);
+    }
+
+    #[test]
+    fn landing_on_go_can_pay_double() {
+        let mut session = Session::playing(2);
+        assert_eq!(session.salary(0), GO_SALARY);
+
+        session.rules.double_go_salary = true;
+        assert_eq!(session.salary(0), 2 * GO_SALARY);
+        assert_eq!(session.salary(MEDITERRANEAN), GO_SALARY);
+    }
+
+    #[test]
+    fn declined_deeds_stay_with_the_bank_without_auctions() {
+        let mut session = Session::playing(2);
+        session.rules.auctions = false;
+        session.turn.pending = Some(MEDITERRANEAN);
+
+        assert_eq!(session.decline(0).unwrap(), (MEDITERRANEAN, None));
+        assert!(session.auction.is_none());
+    }
+
+    #[test]
+    fn net_worth_counts_deeds_and_buildings() {
+        let mut session = Session::playing(2);
+        session.players[0].deeds.push(MEDITERRANEAN);
+        session.properties[MEDITERRANEAN].buildings = 2;
+
+        assert_eq!(session.net_worth(0), STARTING_CASH + 60 + 100);
+
+        session.properties[MEDITERRANEAN].buildings = 0;
+        session.properties[MEDITERRANEAN].mortgaged = true;
+        assert_eq!(session.net_worth(0), STARTING_CASH + 30);
+    }
+
+    #[test]
+    fn turn_limit_crowns_the_wealthiest() {
+        let mut session = Session::playing(2);
+        session.rules.max_turns = Some(1);
+        session.players[1].cash += 1;
+        session.turn.rolled = true;
+
+        assert!(matches!(
+            session.end_turn(0).unwrap(),
+            Outcome::GameOver { winner: 1 }
+        ));
+    }
+
+    #[test]
+    fn rules_only_change_in_the_lobby() {
+        let mut session = Session::lobby(2);
+        let rules = HouseRules {
+            auctions: false,
+            ..HouseRules::default()
+        };
+
+        session.set_rules(rules.clone()).unwrap();
+        assert!(!session.rules().auctions);
+
+        session.started = true;
+        assert_eq!(session.set_rules(rules), Err(GameError::AlreadyStarted));
+    }
+}
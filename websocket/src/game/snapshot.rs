@@ -0,0 +1,288 @@
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::game::lobby::Phase;
+use crate::game::rules::HouseRules;
+use crate::game::trade::Bundle;
+use crate::game::Session;
+
+/// Chat messages included in a snapshot.
+const RECENT_CHAT: usize = 50;
+
+/// Everything a client needs to draw the game from scratch. Players are
+/// referred to by username and deeds by board position.
+#[derive(Debug, Serialize)]
+pub struct Snapshot {
+    /// Number of the last event sent to the requesting player, which the
+    /// snapshot already reflects. `SYNC` picks up from here. Absent in an
+    /// archive.
+    seq: Option<u64>,
+    started: bool,
+    phase: Phase,
+    host: Option<String>,
+    rules: HouseRules,
+    /// Usernames in turn order, empty until the game starts.
+    order: Vec<String>,
+    turn: Option<TurnSnapshot>,
+    players: Vec<PlayerSnapshot>,
+    properties: Vec<PropertySnapshot>,
+    houses: u8,
+    hotels: u8,
+    jackpot: u32,
+    auction: Option<AuctionSnapshot>,
+    /// Pending trades the requesting player is a party to, or all of them
+    /// in an archive.
+    trades: Vec<TradeSnapshot>,
+    /// Mortgaged deeds the requesting player still has to decide on.
+    mortgage_decisions: Vec<usize>,
+    chat: Vec<ChatSnapshot>,
+}
+
+#[derive(Debug, Serialize)]
+struct TurnSnapshot {
+    player: String,
+    rolled: bool,
+    /// Deed waiting on a BUY or DECLINE.
+    pending: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[allow(clippy::struct_excessive_bools)]
+struct PlayerSnapshot {
+    username: String,
+    connected: bool,
+    ready: bool,
+    muted: bool,
+    position: usize,
+    /// Failed rolls in jail, if jailed.
+    jailed: Option<u8>,
+    cash: u32,
+    debt: u32,
+    deeds: Vec<usize>,
+    jail_cards: usize,
+    bankrupt: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct PropertySnapshot {
+    position: usize,
+    owner: String,
+    buildings: u8,
+    mortgaged: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct AuctionSnapshot {
+    position: usize,
+    bidder: Option<String>,
+    bid: u32,
+    /// Seconds left before the auction closes unless someone bids.
+    remaining: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct TradeSnapshot {
+    id: usize,
+    proposer: String,
+    target: String,
+    offer: BundleSnapshot,
+    request: BundleSnapshot,
+}
+
+#[derive(Debug, Serialize)]
+struct BundleSnapshot {
+    cash: u32,
+    deeds: Vec<usize>,
+    jail_cards: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatSnapshot {
+    id: usize,
+    username: String,
+    msg: String,
+}
+
+impl From<&Bundle> for BundleSnapshot {
+    fn from(bundle: &Bundle) -> Self {
+        BundleSnapshot {
+            cash: bundle.cash,
+            deeds: bundle.deeds.clone(),
+            jail_cards: bundle.jail_cards,
+        }
+    }
+}
+
+impl Session {
+    /// The game as seen by `player_id`.
+    pub fn snapshot(&self, player_id: usize) -> Snapshot {
+        self.view(Some(player_id))
+    }
+
+    /// The whole game, with every pending trade, for keeping once it is over.
+    pub fn archive(&self) -> Snapshot {
+        self.view(None)
+    }
+
+    fn view(&self, viewer: Option<usize>) -> Snapshot {
+        let username = |id: usize| self.players[id].username.clone();
+
+        Snapshot {
+            seq: viewer.map(|viewer| self.players[viewer].seq),
+            started: self.started,
+            phase: self.phase(),
+            host: self.host.clone(),
+            rules: self.rules.clone(),
+            order: self.order.iter().map(|id| username(*id)).collect(),
+            turn: self.started.then(|| TurnSnapshot {
+                player: username(self.turn.player),
+                rolled: self.turn.rolled,
+                pending: self.turn.pending,
+            }),
+            players: self
+                .players
+                .iter()
+                .filter(|player| !self.kicked.contains(&player.id))
+                .map(|player| PlayerSnapshot {
+                    username: player.username.clone(),
+                    connected: player.connected,
+                    ready: player.ready,
+                    muted: self.is_muted(player.id),
+                    position: player.position,
+                    jailed: player.jailed,
+                    cash: player.cash,
+                    debt: self
+                        .debts
+                        .iter()
+                        .filter(|debt| debt.debtor == player.id)
+                        .map(|debt| debt.amount)
+                        .sum(),
+                    deeds: player.deeds.clone(),
+                    jail_cards: player.jail_cards.len(),
+                    bankrupt: player.bankrupt,
+                })
+                .collect(),
+            properties: self
+                .properties
+                .iter()
+                .enumerate()
+                .filter_map(|(position, state)| {
+                    Some(PropertySnapshot {
+                        position,
+                        owner: username(self.owner_of(position)?),
+                        buildings: state.buildings,
+                        mortgaged: state.mortgaged,
+                    })
+                })
+                .collect(),
+            houses: self.houses,
+            hotels: self.hotels,
+            jackpot: self.jackpot,
+            auction: self.auction.as_ref().map(|auction| AuctionSnapshot {
+                position: auction.position,
+                bidder: auction.highest.map(|(bidder, _)| username(bidder)),
+                bid: auction.highest.map_or(0, |(_, bid)| bid),
+                remaining: auction
+                    .deadline
+                    .saturating_duration_since(Instant::now())
+                    .as_secs(),
+            }),
+            trades: self
+                .trades
+                .iter()
+                .filter(|trade| {
+                    viewer.is_none_or(|viewer| trade.proposer == viewer || trade.target == viewer)
+                })
+                .map(|trade| TradeSnapshot {
+                    id: trade.id,
+                    proposer: username(trade.proposer),
+                    target: username(trade.target),
+                    offer: (&trade.offer).into(),
+                    request: (&trade.request).into(),
+                })
+                .collect(),
+            mortgage_decisions: viewer
+                .map(|viewer| self.mortgage_decisions(viewer))
+                .unwrap_or_default(),
+            chat: self
+                .chat
+                .iter()
+                .skip(self.chat.len().saturating_sub(RECENT_CHAT))
+                .map(|message| ChatSnapshot {
+                    id: message.msg_id,
+                    username: username(message.user_id),
+                    msg: message.content.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::back::{Event, Message};
+
+    const MEDITERRANEAN: usize = 1;
+
+    #[test]
+    fn deeds_are_listed_with_their_owner() {
+        let mut session = Session::playing(2);
+        session.players[1].deeds.push(MEDITERRANEAN);
+        session.properties[MEDITERRANEAN].mortgaged = true;
+
+        let snapshot = session.snapshot(0);
+
+        assert!(snapshot.started);
+        assert_eq!(snapshot.order, ["player0", "player1"]);
+        assert_eq!(snapshot.turn.unwrap().player, "player0");
+        assert_eq!(snapshot.properties.len(), 1);
+        assert_eq!(snapshot.properties[0].owner, "player1");
+        assert!(snapshot.properties[0].mortgaged);
+    }
+
+    #[test]
+    fn only_the_requesters_trades_are_shown() {
+        let mut session = Session::playing(3);
+        let offer = Bundle {
+            cash: 10,
+            ..Bundle::default()
+        };
+        session
+            .propose_trade(1, "player2", offer, Bundle::default())
+            .unwrap();
+
+        assert!(session.snapshot(0).trades.is_empty());
+        assert_eq!(session.snapshot(2).trades.len(), 1);
+        assert_eq!(session.archive().trades.len(), 1);
+    }
+
+    #[test]
+    fn snapshots_carry_the_requesters_last_seq() {
+        let mut session = Session::lobby(2);
+        session.publish(&Event::Msg(Message::new("player0", "hi")));
+        session.deliver(1, &Event::Msg(Message::new("player0", "hi")));
+
+        assert_eq!(session.snapshot(0).seq, Some(1));
+        assert_eq!(session.snapshot(1).seq, Some(2));
+        assert_eq!(session.archive().seq, None);
+    }
+
+    #[test]
+    fn kicked_players_are_left_out() {
+        let mut session = Session::lobby(3);
+        session.kick(0, "player1").unwrap();
+
+        let snapshot = session.snapshot(0);
+        let usernames = snapshot
+            .players
+            .iter()
+            .map(|player| player.username.as_str())
+            .collect::<Vec<_>>();
+
+        assert!(!snapshot.started);
+        assert!(snapshot.turn.is_none());
+        assert_eq!(usernames, ["player0", "player2"]);
+    }
+}
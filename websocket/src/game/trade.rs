@@ -0,0 +1,312 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::error::{GameError, Result};
+use crate::game::Session;
+
+/// One side of a trade.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bundle {
+    pub cash: u32,
+    pub deeds: Vec<usize>,
+    pub jail_cards: usize,
+}
+
+impl Bundle {
+    /// Sorts the deeds and drops repeats, so none is handed over twice.
+    pub fn normalized(mut self) -> Bundle {
+        self.deeds.sort_unstable();
+        self.deeds.dedup();
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trade {
+    pub id: usize,
+    pub proposer: usize,
+    pub target: usize,
+    /// What the proposer gives up.
+    pub offer: Bundle,
+    /// What the proposer wants from the target.
+    pub request: Bundle,
+}
+
+impl Session {
+    pub fn propose_trade(
+        &mut self,
+        proposer: usize,
+        target: &str,
+        offer: Bundle,
+        request: Bundle,
+    ) -> Result<Trade> {
+        let Some(target) = self.player_id_by_username(target) else {
+            return Err(GameError::UnknownTradePartner);
+        };
+
+        if target == proposer || !self.can_trade(proposer) || !self.can_trade(target) {
+            return Err(GameError::InvalidTrade);
+        }
+
+        if !self.holds(proposer, &offer) || !self.holds(target, &request) {
+            return Err(GameError::AssetsUnavailable);
+        }
+
+        self.trade_count += 1;
+        let trade = Trade {
+            id: self.trade_count,
+            proposer,
+            target,
+            offer,
+            request,
+        };
+        self.trades.push(trade.clone());
+
+        Ok(trade)
+    }
+
+    /// Swaps both bundles, failing if either side no longer holds what it put up.
+    pub fn accept_trade(&mut self, player_id: usize, id: usize) -> Result<Trade> {
+        let trade = self.take_trade(id, |trade| trade.target == player_id)?;
+
+        if !self.can_trade(trade.proposer) || !self.can_trade(trade.target) {
+            return Err(GameError::InvalidTrade);
+        }
+
+        if !self.holds(trade.proposer, &trade.offer) || !self.holds(trade.target, &trade.request) {
+            return Err(GameError::AssetsUnavailable);
+        }
+
+        self.hand_over(trade.proposer, trade.target, &trade.offer);
+        self.hand_over(trade.target, trade.proposer, &trade.request);
+
+        Ok(trade)
+    }
+
+    /// Withdraws or turns down a trade; either party may do so.
+    pub fn reject_trade(&mut self, player_id: usize, id: usize) -> Result<Trade> {
+        self.take_trade(id, |trade| {
+            trade.target == player_id || trade.proposer == player_id
+        })
+    }
+
+    /// Turns down trade `id` and proposes `offer` for `request` back to its proposer.
+    pub fn counter_trade(
+        &mut self,
+        player_id: usize,
+        id: usize,
+        offer: Bundle,
+        request: Bundle,
+    ) -> Result<(Trade, Trade)> {
+        let Some(original) = self
+            .trades
+            .iter()
+            .find(|trade| trade.id == id && trade.target == player_id)
+            .cloned()
+        else {
+            return Err(GameError::UnknownTrade);
+        };
+
+        let proposer = self.players[original.proposer].username.clone();
+        let counter = self.propose_trade(player_id, &proposer, offer, request)?;
+        self.trades.retain(|trade| trade.id != id);
+
+        Ok((original, counter))
+    }
+
+    fn take_trade(&mut self, id: usize, party: impl Fn(&Trade) -> bool) -> Result<Trade> {
+        let Some(index) = self
+            .trades
+            .iter()
+            .position(|trade| trade.id == id && party(trade))
+        else {
+            return Err(GameError::UnknownTrade);
+        };
+
+        Ok(self.trades.remove(index))
+    }
+
+    /// Whether `player_id` is still in the game to trade, i.e. neither
+    /// bankrupt nor kicked.
+    fn can_trade(&self, player_id: usize) -> bool {
+        !self.players[player_id].bankrupt && !self.kicked.contains(&player_id)
+    }
+
+    /// Whether `player_id` can hand over `bundle`. Deeds in a color group
+    /// with buildings cannot change hands until the buildings are sold.
+    fn holds(&self, player_id: usize, bundle: &Bundle) -> bool {
+        let player = &self.players[player_id];
+
+        player.cash >= bundle.cash
+            && player.jail_cards.len() >= bundle.jail_cards
+            && bundle
+                .deeds
+                .iter()
+                .all(|deed| player.deeds.contains(deed) && !self.group_has_buildings(*deed))
+    }
+
+    fn hand_over(&mut self, from: usize, to: usize, bundle: &Bundle) {
+        self.players[from].cash -= bundle.cash;
+        self.players[to].cash += bundle.cash;
+
+        for _ in 0..bundle.jail_cards {
+            let card = self.players[from].jail_cards.pop().unwrap();
+            self.players[to].jail_cards.push(card);
+        }
+
+        self.players[from]
+            .deeds
+            .retain(|deed| !bundle.deeds.contains(deed));
+        for deed in &bundle.deeds {
+            self.receive_deed(to, *deed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use house_rules::STARTING_CASH;
+
+    const MEDITERRANEAN: usize = 1;
+    const BALTIC: usize = 3;
+    const READING_RAILROAD: usize = 5;
+
+    /// Player 0 offers Mediterranean and $100 for player 1's railroad.
+    fn proposed(session: &mut Session) -> Trade {
+        session.players[0].deeds.push(MEDITERRANEAN);
+        session.players[1].deeds.push(READING_RAILROAD);
+
+        session
+            .propose_trade(
+                0,
+                "player1",
+                Bundle {
+                    cash: 100,
+                    deeds: vec![MEDITERRANEAN],
+                    jail_cards: 0,
+                },
+                Bundle {
+                    deeds: vec![READING_RAILROAD],
+                    ..Bundle::default()
+                },
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn accepting_swaps_both_bundles() {
+        let mut session = Session::playing(2);
+        let trade = proposed(&mut session);
+
+        session.accept_trade(1, trade.id).unwrap();
+
+        assert_eq!(session.players[0].cash, STARTING_CASH - 100);
+        assert_eq!(session.players[1].cash, STARTING_CASH + 100);
+        assert_eq!(session.players[0].deeds, vec![READING_RAILROAD]);
+        assert_eq!(session.players[1].deeds, vec![MEDITERRANEAN]);
+    }
+
+    #[test]
+    fn failed_accept_leaves_both_players_untouched() {
+        let mut session = Session::playing(2);
+        let trade = proposed(&mut session);
+
+        // The proposer spends the cash they put up before the trade is accepted.
+        session.players[0].cash = 50;
+
+        assert_eq!(
+            session.accept_trade(1, trade.id).unwrap_err(),
+            GameError::AssetsUnavailable
+        );
+        assert_eq!(session.players[0].cash, 50);
+        assert_eq!(session.players[1].cash, STARTING_CASH);
+        assert_eq!(session.players[0].deeds, vec![MEDITERRANEAN]);
+        assert_eq!(session.players[1].deeds, vec![READING_RAILROAD]);
+        assert!(session.trades.is_empty());
+    }
+
+    #[test]
+    fn deeds_in_a_built_group_cannot_be_accepted() {
+        let mut session = Session::playing(2);
+        let trade = proposed(&mut session);
+
+        session.players[0].deeds.push(BALTIC);
+        session.properties[BALTIC].buildings = 1;
+
+        assert_eq!(
+            session.accept_trade(1, trade.id).unwrap_err(),
+            GameError::AssetsUnavailable
+        );
+        assert_eq!(session.players[1].deeds, vec![READING_RAILROAD]);
+        assert_eq!(session.players[1].cash, STARTING_CASH);
+    }
+
+    #[test]
+    fn players_out_of_the_game_cannot_trade() {
+        let mut session = Session::playing(3);
+        let trade = proposed(&mut session);
+
+        session.players[0].bankrupt = true;
+
+        assert_eq!(
+            session.accept_trade(1, trade.id).unwrap_err(),
+            GameError::InvalidTrade
+        );
+        assert_eq!(session.players[1].deeds, vec![READING_RAILROAD]);
+        assert_eq!(session.players[1].cash, STARTING_CASH);
+        assert!(session.trades.is_empty());
+
+        assert_eq!(
+            session
+                .propose_trade(0, "player1", Bundle::default(), Bundle::default())
+                .unwrap_err(),
+            GameError::InvalidTrade
+        );
+    }
+
+    #[test]
+    fn kicking_drops_open_trades() {
+        let mut session = Session::playing(3);
+        let trade = proposed(&mut session);
+
+        session.kick(0, "player1").unwrap();
+
+        assert!(session.trades.is_empty());
+        assert_eq!(
+            session.accept_trade(1, trade.id).unwrap_err(),
+            GameError::UnknownTrade
+        );
+    }
+
+    #[test]
+    fn only_the_target_accepts() {
+        let mut session = Session::playing(2);
+        let trade = proposed(&mut session);
+
+        assert_eq!(
+            session.accept_trade(0, trade.id).unwrap_err(),
+            GameError::UnknownTrade
+        );
+        session.reject_trade(0, trade.id).unwrap();
+        assert!(session.trades.is_empty());
+    }
+
+    #[test]
+    fn cannot_offer_what_is_not_held() {
+        let mut session = Session::playing(2);
+
+        let offer = Bundle {
+            deeds: vec![MEDITERRANEAN],
+            ..Bundle::default()
+        };
+
+        assert_eq!(
+            session
+                .propose_trade(0, "player1", offer, Bundle::default())
+                .unwrap_err(),
+            GameError::AssetsUnavailable
+        );
+        assert!(session.trades.is_empty());
+    }
+}
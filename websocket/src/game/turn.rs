@@ -0,0 +1,227 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::game::board::{BOARD_SIZE, JAIL_POSITION};
+use crate::game::error::{GameError, Result};
+use crate::game::property::RentRule;
+use crate::game::{Outcome, Session};
+
+const MAX_DOUBLES: u8 = 3;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Turn {
+    pub(super) player: usize,
+    doubles: u8,
+    pub(super) rolled: bool,
+    pub(super) pending: Option<usize>,
+}
+
+impl Turn {
+    pub(super) fn new(player: usize) -> Turn {
+        Turn {
+            player,
+            ..Turn::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RollResult {
+    pub dice: [u8; 2],
+    pub position: usize,
+    pub outcomes: Vec<Outcome>,
+}
+
+impl RollResult {
+    pub fn is_doubles(&self) -> bool {
+        self.dice[0] == self.dice[1]
+    }
+
+    pub fn total(&self) -> usize {
+        usize::from(self.dice[0] + self.dice[1])
+    }
+}
+
+impl Session {
+    pub fn roll(&mut self, player_id: usize) -> Result<RollResult> {
+        if player_id != self.turn.player {
+            return Err(GameError::NotYourTurn);
+        }
+
+        if self.turn.pending.is_some() {
+            return Err(GameError::PurchasePending);
+        }
+
+        if self.auction.is_some() {
+            return Err(GameError::AuctionInProgress);
+        }
+
+        if self.has_mortgage_decisions(player_id) {
+            return Err(GameError::MortgageDecisionPending);
+        }
+
+        if self.turn.rolled {
+            return Err(GameError::AlreadyRolled);
+        }
+
+        let dice = [self.rng.gen_range(1..=6), self.rng.gen_range(1..=6)];
+
+        let mut result = RollResult {
+            dice,
+            position: self.players[player_id].position,
+            outcomes: vec![],
+        };
+
+        if let Some(attempts) = self.players[player_id].jailed {
+            self.roll_in_jail(player_id, attempts, &mut result);
+
+            return Ok(result);
+        }
+
+        if result.is_doubles() {
+            self.turn.doubles += 1;
+        } else {
+            self.turn.rolled = true;
+        }
+
+        if self.turn.doubles == MAX_DOUBLES {
+            self.send_to_jail(player_id);
+
+            result.position = JAIL_POSITION;
+            result.outcomes.push(Outcome::Jailed { player: player_id });
+
+            return Ok(result);
+        }
+
+        self.move_by(player_id, &mut result);
+
+        Ok(result)
+    }
+
+    /// Hands the turn on, or ends the game once the turn limit is reached.
+    pub fn end_turn(&mut self, player_id: usize) -> Result<Outcome> {
+        if self.winner.is_some() {
+            return Err(GameError::GameFinished);
+        }
+
+        if player_id != self.turn.player {
+            return Err(GameError::NotYourTurn);
+        }
+
+        if self.turn.pending.is_some() {
+            return Err(GameError::PurchasePending);
+        }
+
+        if self.auction.is_some() {
+            return Err(GameError::AuctionInProgress);
+        }
+
+        if self.has_mortgage_decisions(player_id) {
+            return Err(GameError::MortgageDecisionPending);
+        }
+
+        if !self.turn.rolled {
+            return Err(GameError::NotRolled);
+        }
+
+        self.turns += 1;
+
+        if self.rules.max_turns.is_some_and(|max| self.turns >= max) {
+            let winner = self
+                .order
+                .iter()
+                .filter(|player| !self.players[**player].bankrupt)
+                .max_by_key(|player| self.net_worth(**player))
+                .copied()
+                .unwrap();
+
+            self.winner = Some(winner);
+            return Ok(Outcome::GameOver { winner });
+        }
+
+        Ok(Outcome::Turn {
+            player: self.pass_turn(),
+        })
+    }
+
+    /// Hands the turn to the next player in turn order who is still in the game.
+    pub(super) fn pass_turn(&mut self) -> usize {
+        let seat = self
+            .order
+            .iter()
+            .position(|player| *player == self.turn.player)
+            .unwrap();
+
+        let next = (1..=self.order.len())
+            .map(|offset| self.order[(seat + offset) % self.order.len()])
+            .find(|player| !self.players[*player].bankrupt)
+            .unwrap_or(self.turn.player);
+
+        self.turn = Turn::new(next);
+
+        next
+    }
+
+    /// Moves `player_id` forward by the dice in `result`, paying the salary
+    /// for passing Go and resolving the space they land on.
+    pub(super) fn move_by(&mut self, player_id: usize, result: &mut RollResult) {
+        let target = result.position + result.total();
+        if target >= BOARD_SIZE {
+            let salary = self.salary(target % BOARD_SIZE);
+            self.players[player_id].cash += salary;
+            result.outcomes.push(Outcome::Salary {
+                player: player_id,
+                amount: salary,
+            });
+        }
+
+        result.position = target % BOARD_SIZE;
+        self.players[player_id].position = result.position;
+
+        let mut landing = self.land(
+            player_id,
+            RentRule::Standard {
+                dice: result.total(),
+            },
+        );
+        result.outcomes.append(&mut landing);
+    }
+
+    /// Sends `player_id` straight to jail and ends their rolling for the turn.
+    pub(super) fn send_to_jail(&mut self, player_id: usize) {
+        let player = &mut self.players[player_id];
+        player.position = JAIL_POSITION;
+        player.jailed = Some(0);
+
+        if self.turn.player == player_id {
+            self.turn.rolled = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_active_player_takes_a_turn() {
+        let mut session = Session::playing(2);
+
+        assert_eq!(session.roll(1).unwrap_err(), GameError::NotYourTurn);
+        assert_eq!(session.end_turn(1).unwrap_err(), GameError::NotYourTurn);
+    }
+
+    #[test]
+    fn turn_passes_in_order_once_rolled() {
+        let mut session = Session::playing(3);
+        assert_eq!(session.end_turn(0).unwrap_err(), GameError::NotRolled);
+
+        for next in [1, 2, 0] {
+            session.turn.rolled = true;
+            assert!(matches!(
+                session.end_turn(session.turn.player).unwrap(),
+                Outcome::Turn { player } if player == next
+            ));
+        }
+    }
+}
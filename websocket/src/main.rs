@@ -1,26 +1,35 @@
-#![feature(lazy_cell)]
-#![feature(concat_bytes)]
 #![warn(clippy::pedantic)]
 #![deny(rust_2018_idioms)]
 
 use std::io::Read;
 use std::os::unix::net::SocketAddr;
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 use async_std::os::unix::net::{UnixListener, UnixStream};
-use eyre::Result;
-use log::*;
+use async_std::prelude::FutureExt;
+use eyre::{Result, WrapErr};
+use log::{error, info, LevelFilter};
 use parking_lot::Mutex;
+use serde_json::json;
 use soketto::handshake::server::Response;
 use soketto::handshake::Server;
 
 use crate::api::back::EventHandler;
 use crate::api::front::CommandHandler;
+use crate::api::wire::{Client, Protocol};
+use crate::game::rules::HouseRules;
 
 mod api;
 mod game;
 mod util;
 
+/// How long a game may go without anyone connected before it shuts down.
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_mins(10);
+
+/// How often the game is saved when `MONOPOLY_DATA_DIR` is set.
+const DEFAULT_SNAPSHOT_INTERVAL: Duration = Duration::from_mins(1);
+
 static GAME: LazyLock<Arc<Mutex<game::Session>>> =
     LazyLock::new(|| Arc::new(Mutex::new(game::Session::new())));
 
@@ -38,32 +47,52 @@ async fn serve_websocket(stream: UnixStream, addr: SocketAddr) -> Result<()> {
     );
 
     let mut server = Server::new(stream);
+    for protocol in Protocol::SUPPORTED {
+        server.add_protocol(protocol);
+    }
 
-    let websocket_key = {
+    let (websocket_key, protocol, control) = {
         let Ok(req) = server.receive_request().await else {
             error!("Failed to receive connection request for WS (#{})", ws_id);
             return Ok(());
         };
-        info!("Received request for path: {}", req.path());
-        req.key()
+
+        let control = api::control::is_control(req.path());
+        if !control {
+            info!("Received request for path: {}", req.path());
+        }
+
+        (req.key(), Protocol::negotiate(req.protocols()), control)
     };
 
+    info!("WS (#{}) speaks the {:?} protocol", ws_id, protocol);
+
     let accept = Response::Accept {
         key: websocket_key,
-        protocol: None,
+        protocol: if control { None } else { protocol.name() },
     };
 
-    let Ok(_) = server.send_response(&accept).await else {
+    let Ok(()) = server.send_response(&accept).await else {
         error!("Failed to accept WS (#{}) connection", ws_id);
         return Ok(());
     };
 
+    if control {
+        info!("Serving control channel on WS (#{})", ws_id);
+
+        let (send, recv) = server.into_builder().finish();
+        api::control::serve(send, recv, GAME.clone()).await;
+
+        return Ok(());
+    }
+
     let (sender, receiver) = {
         let (send, recv) = server.into_builder().finish();
         (Arc::new(Mutex::new(send)), Arc::new(Mutex::new(recv)))
     };
 
-    util::sync!(sender.lock().send_text("CONNECTED")).unwrap();
+    let client = Client::new(sender, protocol);
+    client.reply("CONNECTED".into(), &json!({ "status": "CONNECTED" }));
 
     let (send, recv) = std::sync::mpsc::channel();
 
@@ -71,17 +100,17 @@ async fn serve_websocket(stream: UnixStream, addr: SocketAddr) -> Result<()> {
     let mut event_handler = EventHandler::new(ws_id, recv, GAME.clone());
 
     std::thread::scope(|s| {
-        let send1 = sender.clone();
+        let client1 = client.clone();
         let recv1 = receiver.clone();
         s.spawn(move || {
-            let sender = send1.clone();
+            let client = client1;
             let receiver = recv1.clone();
 
             loop {
-                let command = comm_handler.pump_command(receiver.clone());
+                let command = comm_handler.pump_command(&receiver, client.protocol());
 
                 if let Some(command) = command {
-                    comm_handler.execute_command(&command, sender.clone());
+                    comm_handler.execute_command(&command, &client);
                 }
 
                 if comm_handler.is_kill() {
@@ -90,14 +119,14 @@ async fn serve_websocket(stream: UnixStream, addr: SocketAddr) -> Result<()> {
             }
         });
 
-        let send2 = sender.clone();
+        let client2 = client.clone();
         s.spawn(move || {
-            let sender = send2.clone();
+            let client = client2;
             loop {
                 let event = event_handler.pump_event();
 
-                if let Some(event) = event {
-                    event_handler.execute_event(event, sender.clone());
+                if let Some((seq, event)) = event {
+                    event_handler.execute_event(seq, event, &client);
                 }
 
                 if event_handler.is_kill() {
@@ -110,6 +139,25 @@ async fn serve_websocket(stream: UnixStream, addr: SocketAddr) -> Result<()> {
     Ok(())
 }
 
+/// Resolves once nobody has been connected for `timeout`, after archiving
+/// the game and removing its socket.
+async fn watch_idle(timeout: Duration) -> Result<()> {
+    loop {
+        let idle = GAME.lock().idle_for();
+        let remaining = idle.map_or(timeout, |idle| timeout.saturating_sub(idle));
+
+        if remaining.is_zero() {
+            break;
+        }
+
+        async_std::task::sleep(remaining).await;
+    }
+
+    info!("Idle for {}s, shutting down", timeout.as_secs());
+
+    api::control::shut_down(&GAME)
+}
+
 fn main() -> Result<()> {
     simple_logger::SimpleLogger::new()
         .with_level(LevelFilter::Debug)
@@ -128,18 +176,41 @@ fn main() -> Result<()> {
         "MISSING MONOPOLY_CHOWN_ID ENV VAR"
     );
 
+    let rules = HouseRules::from_env().wrap_err("INVALID MONOPOLY_HOUSE_RULES")?;
+    GAME.lock().set_rules(rules)?;
+
     async_std::task::block_on(async move {
         let sock_addr = std::env::var("MONOPOLY_GAME_PATH")?;
 
+        api::persist::restore(&GAME)?;
+
         let server = UnixListener::bind(&sock_addr).await?;
         info!("Listening on {}", &sock_addr);
 
-        std::os::unix::fs::chown(sock_addr, Some(33), Some(33))?;
+        std::os::unix::fs::chown(&sock_addr, Some(33), Some(33))?;
+        api::internal::report(&GAME);
 
-        while let Ok((stream, addr)) = server.accept().await {
-            async_std::task::spawn(serve_websocket(stream, addr));
-        }
+        let idle_timeout = std::env::var("MONOPOLY_IDLE_TIMEOUT")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .map_or(DEFAULT_IDLE_TIMEOUT, Duration::from_secs);
+
+        let snapshot_interval = std::env::var("MONOPOLY_SNAPSHOT_INTERVAL")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .map_or(DEFAULT_SNAPSHOT_INTERVAL, Duration::from_secs);
+
+        let accept = async {
+            while let Ok((stream, addr)) = server.accept().await {
+                async_std::task::spawn(serve_websocket(stream, addr));
+            }
+
+            Ok(())
+        };
 
-        Ok(())
+        accept
+            .race(watch_idle(idle_timeout))
+            .race(api::persist::keep_snapshots(&GAME, snapshot_interval))
+            .await
     })
 }
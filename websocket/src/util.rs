@@ -1,7 +1,11 @@
 macro_rules! sync {
-    ($future: expr) => {
-        async_std::task::block_on(async { $future.await })
-    };
+    ($future: expr) => {{
+        // Callers lock a socket half for the duration of a single send or
+        // receive; the guard never outlives this `block_on`.
+        #[allow(clippy::await_holding_lock)]
+        let result = async_std::task::block_on(async { $future.await });
+        result
+    }};
 }
 
-pub(crate) use sync;
\ No newline at end of file
+pub(crate) use sync;